@@ -1,18 +0,0 @@
-use std::fmt::{Display, Formatter};
-
-pub type Result<T> = std::result::Result<T, Error>;
-pub struct Error {
-    message: String,
-}
-
-impl Error {
-    pub fn new(message: String) -> Self {
-        Self { message }
-    }
-}
-
-impl Display for Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Error: {}", self.message)
-    }
-}
@@ -1,46 +1,74 @@
-use std::{env, io, process};
+use std::{env, io};
 
-use error::{Error, Result};
-use transmute::{transmute, TransmutationKind};
+use transmuter_common::{
+    config::Config,
+    error::{Error, ErrorFormat, Result},
+    transmute::pipeline::Stage,
+    Registry,
+};
 
-mod error;
-mod transmute;
+/// The arguments following the kind are the arguments of the transmutation,
+/// e.g. `truncate 80`. Two options may appear anywhere among them:
+/// `--invert` undoes the transmutation, e.g. `reverse-talk --invert`, and
+/// `--error-format json` prints the errors as JSON objects.
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let transmutation_kind = parse_args(&args).unwrap_or_else(|err| {
-        eprintln!("{err}");
-        process::exit(1);
-    });
+    let error_format = ErrorFormat::from_args(&args);
+    let (args, invert) = take_options(&args).unwrap_or_else(|err| error_format.exit(&err));
+
+    let mut registry = Registry::new();
+    Config::load()
+        .and_then(|config| registry.register_aliases(&config.aliases))
+        .unwrap_or_else(|err| error_format.exit(&err));
+    let transmutation = parse_args(&registry, &args)
+        .and_then(|stage| match invert {
+            true => stage.invert(),
+            false => Ok(stage),
+        })
+        .unwrap_or_else(|err| error_format.exit(&err));
     let input = {
         println!("Please enter your text.");
         let mut input = String::new();
         io::stdin()
             .read_line(&mut input)
-            .expect("Unable to read from stdin");
+            .unwrap_or_else(|err| error_format.exit(&err.into()));
         input
     };
-    println!("{}", transmute(&input, transmutation_kind));
+    let output = transmutation
+        .transmute(&input)
+        .unwrap_or_else(|err| error_format.exit(&err));
+    println!("{output}");
+}
+
+/// Removes the `--invert` and `--error-format` options from the arguments,
+/// returns the rest and whether to invert the transmutation.
+fn take_options(args: &[String]) -> Result<(Vec<String>, bool)> {
+    let mut rest = Vec::new();
+    let mut invert = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--invert" => invert = true,
+            "--error-format" => {
+                args.next()
+                    .map_or("", |v| v.as_str())
+                    .parse::<ErrorFormat>()?;
+            }
+            arg if arg.starts_with("--error-format=") => {
+                arg["--error-format=".len()..].parse::<ErrorFormat>()?;
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((rest, invert))
 }
 
-fn parse_args(args: &[String]) -> Result<TransmutationKind> {
+fn parse_args<'a>(registry: &'a Registry, args: &[String]) -> Result<Stage<'a>> {
     if args.len() < 2 {
-        return Err(Error::new(format!(
+        return Err(Error::InvalidArgument(format!(
             "You have to provide transmutation kind as CLI argument.\nFollowing are supported: {}",
-            stringify_possible_transmutations()
+            registry.stringify_possible_transmutations()
         )));
     }
-    TransmutationKind::from_str(&args[1]).ok_or(Error::new(format!(
-        "The unsupported transmutation provided: {}.\nFollowing are supported: {}",
-        String::from(&args[1]),
-        stringify_possible_transmutations()
-    )))
-}
-
-fn stringify_possible_transmutations() -> String {
-    let transmutations = TransmutationKind::all_variants()
-        .iter()
-        .map(|v| v.to_string())
-        .collect::<Vec<String>>()
-        .join(", ");
-    format!("[{transmutations}]")
+    registry.stage(&args[1], &args[2..])
 }
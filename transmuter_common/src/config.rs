@@ -0,0 +1,170 @@
+use std::{env, fs, io, path::PathBuf};
+
+use serde_json::Value;
+
+use crate::{
+    error::{Error, Result},
+    transmute::{arguments::split_stages, data::DataFormat},
+};
+
+/// Environment variable overriding the path of the configuration file.
+pub const CONFIG_VARIABLE: &str = "TRANSMUTER_CONFIG";
+
+/// Settings of the user read from `~/.config/transmuter/config.toml`, e.g.
+///
+/// ```toml
+/// [aliases]
+/// ticket-slug = ["lowercase", "no-spaces", "slugify"]
+/// short-title = ["title-case", "truncate 40"]
+/// ```
+///
+/// An alias expands to its stages in order, each one a transmutation or
+/// another alias, written the same way as a stage of a pipeline.
+#[derive(Debug, Default)]
+pub struct Config {
+    pub aliases: Vec<Alias>,
+}
+
+/// Named sequence of transmutations defined by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alias {
+    pub name: String,
+    /// The name of every stage followed by its arguments.
+    pub stages: Vec<Vec<String>>,
+}
+
+impl Config {
+    /// The file set by `TRANSMUTER_CONFIG`, otherwise `transmuter/config.toml`
+    /// in `$XDG_CONFIG_HOME` or `~/.config`.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_VARIABLE) {
+            return Some(PathBuf::from(path));
+        }
+        let directory = match env::var_os("XDG_CONFIG_HOME") {
+            Some(directory) if !directory.is_empty() => PathBuf::from(directory),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(directory.join("transmuter").join("config.toml"))
+    }
+
+    /// Reads the configuration file, a missing one is an empty configuration
+    /// unless its path is set explicitly.
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::from_str(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                match env::var_os(CONFIG_VARIABLE) {
+                    Some(_) => Err(Error::Io(io::Error::new(
+                        err.kind(),
+                        format!("The config file {} does not exist", path.display()),
+                    ))),
+                    None => Ok(Self::default()),
+                }
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Result<Self> {
+        let config = DataFormat::Toml.parse(text)?;
+        let aliases = match config.get("aliases") {
+            None => Vec::new(),
+            Some(Value::Object(aliases)) => aliases
+                .iter()
+                .map(|(name, stages)| Alias::from_value(name, stages))
+                .collect::<Result<Vec<Alias>>>()?,
+            Some(aliases) => {
+                return Err(Error::InvalidArgument(format!(
+                    "The aliases of the config require a table, provided: {aliases}"
+                )))
+            }
+        };
+
+        Ok(Self { aliases })
+    }
+}
+
+impl Alias {
+    /// The stages are either an array of stages or a single pipeline, e.g.
+    /// `"lowercase|no-spaces"`.
+    fn from_value(name: &str, value: &Value) -> Result<Self> {
+        let invalid = || {
+            Error::InvalidArgument(format!(
+                "The alias {name} requires an array of transmutations, provided: {value}"
+            ))
+        };
+        let pipelines = match value {
+            Value::String(pipeline) => vec![pipeline.as_str()],
+            Value::Array(stages) => stages
+                .iter()
+                .map(|stage| stage.as_str().ok_or_else(invalid))
+                .collect::<Result<Vec<&str>>>()?,
+            _ => return Err(invalid()),
+        };
+        let mut stages = Vec::new();
+        for pipeline in pipelines {
+            stages.extend(split_stages(pipeline)?);
+        }
+        if stages.is_empty() {
+            return Err(Error::InvalidArgument(format!(
+                "The alias {name} has no transmutations"
+            )));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            stages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_aliases() {
+        let config = Config::from_str(
+            r#"
+[aliases]
+ticket-slug = ["lowercase", "no-spaces", "slugify"]
+short = "title-case|truncate 40"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.aliases,
+            vec![
+                Alias {
+                    name: "ticket-slug".to_string(),
+                    stages: vec![
+                        vec!["lowercase".to_string()],
+                        vec!["no-spaces".to_string()],
+                        vec!["slugify".to_string()],
+                    ],
+                },
+                Alias {
+                    name: "short".to_string(),
+                    stages: vec![
+                        vec!["title-case".to_string()],
+                        vec!["truncate".to_string(), "40".to_string()],
+                    ],
+                },
+            ]
+        );
+        assert!(Config::from_str("").unwrap().aliases.is_empty());
+    }
+
+    #[test]
+    fn rejecting_invalid_aliases() {
+        assert!(Config::from_str("[aliases]\nempty = []").is_err());
+        assert!(Config::from_str("[aliases]\nnumbers = [1, 2]").is_err());
+        assert!(Config::from_str("aliases = 3").is_err());
+        let err = Config::from_str("[aliases]\nbroken = [\"lowercase\"").unwrap_err();
+        assert_eq!(err.kind(), "parse");
+    }
+}
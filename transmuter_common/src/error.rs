@@ -0,0 +1,453 @@
+use std::{
+    fmt::{Display, Formatter},
+    io, process,
+    str::FromStr,
+    string::FromUtf8Error,
+};
+
+use colored::Colorize;
+use serde_json::{error::Category, json, Value};
+
+use crate::transmute::data::DataFormat;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Failure of a transmutation or of the transmuter binaries.
+///
+/// Every kind of failure has its own exit code, so scripts can react to them:
+///
+/// | Code | Kind                    | Failure                                        |
+/// |------|-------------------------|------------------------------------------------|
+/// | 2    | `invalid_argument`      | invalid CLI argument, option or input format   |
+/// | 3    | `unknown_transmutation` | no transmutation registered under the name     |
+/// | 4    | `io`                    | reading the input or writing the output failed |
+/// | 5    | `csv_parse`             | the CSV content is malformed                   |
+/// | 6    | `invalid_utf8`          | the input is not valid UTF-8                   |
+/// | 7    | `decode`                | the input is not valid in the decoded encoding |
+/// | 8    | `parse`                 | the JSON, YAML or TOML content is malformed    |
+#[derive(Debug)]
+pub enum Error {
+    InvalidArgument(String),
+    UnknownTransmutation {
+        name: String,
+        supported: Vec<String>,
+    },
+    Io(io::Error),
+    /// The row is the line the malformed record starts on, the column is the
+    /// position of the offending field, both counted from 1.
+    CsvParse {
+        message: String,
+        row: Option<u64>,
+        column: Option<u64>,
+    },
+    /// Byte offset of the first invalid sequence, when known.
+    InvalidUtf8 {
+        offset: Option<usize>,
+    },
+    /// The offset is the byte of the input the invalid sequence starts at.
+    Decode {
+        encoding: String,
+        offset: usize,
+        message: String,
+    },
+    /// The line and column of the malformed structured data, both counted from 1.
+    Parse {
+        format: String,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+    /// Failure of a stage of a [`Pipeline`](crate::transmute::pipeline::Pipeline),
+    /// reported with the kind and exit code of the failure itself.
+    Pipeline {
+        stage: usize,
+        name: String,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Identifier of the kind of the failure used in the JSON error format.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::UnknownTransmutation { .. } => "unknown_transmutation",
+            Self::Io(_) => "io",
+            Self::CsvParse { .. } => "csv_parse",
+            Self::InvalidUtf8 { .. } => "invalid_utf8",
+            Self::Decode { .. } => "decode",
+            Self::Parse { .. } => "parse",
+            Self::Pipeline { source, .. } => source.kind(),
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::InvalidArgument(_) => 2,
+            Self::UnknownTransmutation { .. } => 3,
+            Self::Io(_) => 4,
+            Self::CsvParse { .. } => 5,
+            Self::InvalidUtf8 { .. } => 6,
+            Self::Decode { .. } => 7,
+            Self::Parse { .. } => 8,
+            Self::Pipeline { source, .. } => source.exit_code(),
+        }
+    }
+
+    /// The error as a single line JSON object with the `error` kind, the exit
+    /// `code`, the `message` and the details of the kind, e.g.
+    /// `{"error":"csv_parse","code":5,"message":"...","row":3,"column":2}`.
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_string()
+    }
+
+    /// The object of [`Error::to_json`], for callers adding their own context.
+    pub fn to_json_value(&self) -> Value {
+        let mut object = json!({
+            "error": self.kind(),
+            "code": self.exit_code(),
+            "message": self.to_string(),
+        });
+        self.json_details(&mut object);
+        object
+    }
+
+    fn json_details(&self, object: &mut Value) {
+        match self {
+            Self::UnknownTransmutation { name, supported } => {
+                object["name"] = json!(name);
+                object["supported"] = json!(supported);
+            }
+            Self::CsvParse { row, column, .. } => {
+                object["row"] = json!(row);
+                object["column"] = json!(column);
+            }
+            Self::InvalidUtf8 { offset } => object["offset"] = json!(offset),
+            Self::Decode {
+                encoding, offset, ..
+            } => {
+                object["encoding"] = json!(encoding);
+                object["offset"] = json!(offset);
+            }
+            Self::Parse {
+                format,
+                line,
+                column,
+                ..
+            } => {
+                object["format"] = json!(format);
+                object["line"] = json!(line);
+                object["column"] = json!(column);
+            }
+            Self::Pipeline {
+                stage,
+                name,
+                source,
+            } => {
+                source.json_details(object);
+                object["stage"] = json!(stage);
+                object["stage_name"] = json!(name);
+            }
+            Self::InvalidArgument(_) | Self::Io(_) => {}
+        }
+    }
+}
+
+/// How the errors are printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ErrorFormat {
+    #[default]
+    Text,
+    /// Single line JSON objects of [`Error::to_json`].
+    Json,
+}
+
+impl ErrorFormat {
+    /// Finds the format among the raw arguments, so even the errors of parsing
+    /// them are printed in it.
+    pub fn from_args(args: &[String]) -> Self {
+        let json = args
+            .windows(2)
+            .any(|pair| pair[0] == "--error-format" && pair[1] == "json")
+            || args.iter().any(|arg| arg == "--error-format=json");
+        if json {
+            Self::Json
+        } else {
+            Self::Text
+        }
+    }
+
+    /// Prints the error to stderr. The context, e.g. the job or the file which
+    /// failed, prefixes the text and is added to the JSON object.
+    pub fn print(self, err: &Error, context: Option<(&str, Value)>) {
+        let text = match (self, context) {
+            (Self::Text, None) => err.to_string(),
+            (Self::Text, Some((_, Value::String(context)))) => format!("{context}: {err}"),
+            (Self::Text, Some((name, context))) => format!("[{name} {context}] {err}"),
+            (Self::Json, context) => {
+                let mut object = err.to_json_value();
+                if let Some((name, context)) = context {
+                    object[name] = context;
+                }
+                eprintln!("{object}");
+                return;
+            }
+        };
+        eprintln!("{}{}", "Error: ".red(), text.red());
+    }
+
+    /// Prints the error and exits with the code of its kind.
+    pub fn exit(self, err: &Error) -> ! {
+        self.print(err, None);
+        process::exit(err.exit_code());
+    }
+}
+
+impl FromStr for ErrorFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::InvalidArgument(format!(
+                "The unsupported error format provided: {format}.\nFollowing are supported: [text, json]"
+            ))),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidArgument(message) => write!(f, "{message}"),
+            Self::UnknownTransmutation { name, supported } => write!(
+                f,
+                "The unsupported transmutation provided: {name}.\nFollowing are supported: [{}]",
+                supported.join(", ")
+            ),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::CsvParse {
+                message,
+                row,
+                column,
+            } => match (row, column) {
+                (Some(row), Some(column)) => {
+                    write!(f, "Invalid CSV at row {row}, column {column}: {message}")
+                }
+                (Some(row), None) => write!(f, "Invalid CSV at row {row}: {message}"),
+                _ => write!(f, "Invalid CSV: {message}"),
+            },
+            Self::InvalidUtf8 {
+                offset: Some(offset),
+            } => write!(f, "The input is not valid UTF-8 at byte {offset}"),
+            Self::InvalidUtf8 { offset: None } => write!(f, "The input is not valid UTF-8"),
+            Self::Decode {
+                encoding,
+                offset,
+                message,
+            } => write!(f, "Invalid {encoding} input at byte {offset}: {message}"),
+            Self::Parse {
+                format,
+                line,
+                column,
+                message,
+            } => match (line, column) {
+                (Some(line), Some(column)) => {
+                    write!(
+                        f,
+                        "Invalid {format} at line {line}, column {column}: {message}"
+                    )
+                }
+                (Some(line), None) => write!(f, "Invalid {format} at line {line}: {message}"),
+                _ => write!(f, "Invalid {format}: {message}"),
+            },
+            Self::Pipeline {
+                stage,
+                name,
+                source,
+            } => write!(f, "Stage {stage} ({name}) of the pipeline failed: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Pipeline { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Self {
+        Self::InvalidUtf8 {
+            offset: Some(err.utf8_error().valid_up_to()),
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        let message = err.to_string();
+        match err.into_kind() {
+            csv::ErrorKind::Io(err) => Self::Io(err),
+            csv::ErrorKind::Utf8 { pos, err } => Self::CsvParse {
+                message: format!("the field {} is not valid UTF-8", err.field() + 1),
+                row: pos.map(|p| p.line()),
+                column: Some(err.field() as u64 + 1),
+            },
+            csv::ErrorKind::UnequalLengths {
+                pos,
+                expected_len,
+                len,
+            } => Self::CsvParse {
+                message: format!("expected {expected_len} fields, found {len}"),
+                row: pos.map(|p| p.line()),
+                column: Some(len.min(expected_len) + 1),
+            },
+            _ => Self::CsvParse {
+                message,
+                row: None,
+                column: None,
+            },
+        }
+    }
+}
+
+/// Invalid JSON is a parse error with its position, a value which cannot be
+/// written as JSON an invalid argument.
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        match err.classify() {
+            Category::Io => Self::Io(err.into()),
+            Category::Syntax | Category::Eof => {
+                let (line, column) = (err.line(), err.column());
+                DataFormat::Json.error(&err.to_string(), Some(line), Some(column))
+            }
+            Category::Data => Self::InvalidArgument(format!("Invalid JSON value: {err}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transmute::csv::Csv;
+
+    use super::*;
+
+    #[test]
+    fn reporting_csv_position() {
+        let Err(err) = Csv::from_str("a,b\n1,2\n3,4,5") else {
+            panic!("the CSV should be invalid");
+        };
+        assert_eq!(err.exit_code(), 5);
+        assert!(matches!(
+            err,
+            Error::CsvParse {
+                row: Some(3),
+                column: Some(3),
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Invalid CSV at row 3, column 3: expected 2 fields, found 3"
+        );
+    }
+
+    #[test]
+    fn formatting_json() {
+        let err = Error::UnknownTransmutation {
+            name: "shout".to_string(),
+            supported: vec!["lowercase".to_string()],
+        };
+        let value: Value = serde_json::from_str(&err.to_json()).unwrap();
+        assert_eq!(value["error"], "unknown_transmutation");
+        assert_eq!(value["code"], 3);
+        assert_eq!(value["name"], "shout");
+        assert_eq!(value["supported"][0], "lowercase");
+    }
+
+    #[test]
+    fn keeping_kind_of_pipeline_stage() {
+        let err = Error::Pipeline {
+            stage: 2,
+            name: "csv".to_string(),
+            source: Box::new(Error::InvalidUtf8 { offset: Some(7) }),
+        };
+        assert_eq!(err.exit_code(), 6);
+        let value: Value = serde_json::from_str(&err.to_json()).unwrap();
+        assert_eq!(value["error"], "invalid_utf8");
+        assert_eq!(value["offset"], 7);
+        assert_eq!(value["stage"], 2);
+    }
+
+    #[test]
+    fn distinct_exit_codes() {
+        let errors = [
+            Error::InvalidArgument(String::new()),
+            Error::UnknownTransmutation {
+                name: String::new(),
+                supported: Vec::new(),
+            },
+            Error::Io(io::Error::other("")),
+            Error::CsvParse {
+                message: String::new(),
+                row: None,
+                column: None,
+            },
+            Error::InvalidUtf8 { offset: None },
+            Error::Decode {
+                encoding: String::new(),
+                offset: 0,
+                message: String::new(),
+            },
+            Error::Parse {
+                format: String::new(),
+                line: None,
+                column: None,
+                message: String::new(),
+            },
+        ];
+        let mut codes = errors.iter().map(Error::exit_code).collect::<Vec<i32>>();
+        codes.dedup();
+        assert_eq!(codes, vec![2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn converting_json_errors() {
+        let err = Error::from(serde_json::from_str::<Value>("[1,").unwrap_err());
+        assert_eq!(err.exit_code(), 8);
+        let err = Error::from(serde_json::from_str::<u8>("300").unwrap_err());
+        assert_eq!(err.exit_code(), 2);
+    }
+
+    #[test]
+    fn finding_error_format() {
+        let args = |args: &str| {
+            args.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ErrorFormat::from_args(&args("t --error-format json upper")),
+            ErrorFormat::Json
+        );
+        assert_eq!(
+            ErrorFormat::from_args(&args("t upper --error-format=json")),
+            ErrorFormat::Json
+        );
+        assert_eq!(ErrorFormat::from_args(&args("t json")), ErrorFormat::Text);
+        assert_eq!("json".parse::<ErrorFormat>().unwrap(), ErrorFormat::Json);
+        assert_eq!("yaml".parse::<ErrorFormat>().unwrap_err().exit_code(), 2);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod error;
+pub mod output;
+pub mod registry;
+pub mod transmute;
+
+pub use registry::Registry;
+pub use transmute::{Transmutation, Transmute};
@@ -0,0 +1,23 @@
+use std::io::{self, Write};
+
+use colored::Colorize;
+
+/// Colors everything written through it green, except the text styled already
+/// by the transmutation, e.g. the ANSI output of `markdown`.
+pub struct GreenWriter<W: Write>(pub W);
+
+impl<W: Write> Write for GreenWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) if !text.contains('\x1b') => {
+                write!(self.0, "{}", text.green())?;
+                Ok(buf.len())
+            }
+            _ => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
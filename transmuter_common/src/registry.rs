@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use crate::{
+    config::Alias,
+    error::{Error, Result},
+    transmute::{
+        alias::AliasTransmutation,
+        hash::HashTransmutation,
+        parameterized::ParameterizedTransmutation,
+        pipeline::{Pipeline, Stage},
+        stats::StatsTransmutation,
+        Source, Transmutation, Transmute,
+    },
+};
+
+/// Collection of transmutations available to the transmuter binaries.
+///
+/// A new registry contains all the built-in [`Transmutation`]s followed by the
+/// [`HashTransmutation`]s and the [`StatsTransmutation`] of the input text, and the
+/// [`ParameterizedTransmutation`]s.
+/// Additional transmutations can be added at runtime through [`Registry::register`]
+/// and the aliases of the user through [`Registry::register_aliases`].
+pub struct Registry {
+    transmutations: Vec<Arc<dyn Transmute>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            transmutations: Vec::new(),
+        };
+        Transmutation::all_variants()
+            .into_iter()
+            .for_each(|t| registry.register(t));
+        HashTransmutation::all_variants(Source::Text)
+            .into_iter()
+            .for_each(|t| registry.register(t));
+        registry.register(StatsTransmutation::new(Source::Text));
+        ParameterizedTransmutation::all_variants()
+            .into_iter()
+            .for_each(|t| registry.register(t));
+        registry
+    }
+
+    /// Adds the transmutation to the registry. A transmutation registered under
+    /// an already existing name replaces the previous one.
+    pub fn register(&mut self, transmutation: impl Transmute + 'static) {
+        let transmutation: Arc<dyn Transmute> = Arc::new(transmutation);
+        match self
+            .transmutations
+            .iter_mut()
+            .find(|t| t.name() == transmutation.name())
+        {
+            Some(existing) => *existing = transmutation,
+            None => self.transmutations.push(transmutation),
+        }
+    }
+
+    /// Validates the aliases and registers them after the transmutations they
+    /// expand into, see [`AliasTransmutation::resolve`].
+    pub fn register_aliases(&mut self, aliases: &[Alias]) -> Result<()> {
+        AliasTransmutation::resolve(self, aliases)?
+            .into_iter()
+            .for_each(|alias| self.register(alias));
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Transmute> {
+        self.transmutations
+            .iter()
+            .find(|t| t.name() == name)
+            .map(|t| t.as_ref())
+    }
+
+    /// The registered transmutation shared with the aliases expanding into it.
+    pub(crate) fn shared(&self, name: &str) -> Option<Arc<dyn Transmute>> {
+        self.transmutations
+            .iter()
+            .find(|t| t.name() == name)
+            .cloned()
+    }
+
+    /// Same as [`Registry::get`], but fails with an error listing the supported
+    /// transmutations.
+    pub fn find(&self, name: &str) -> Result<&dyn Transmute> {
+        self.get(name).ok_or_else(|| Error::UnknownTransmutation {
+            name: name.to_string(),
+            supported: self.all_variants().into_iter().map(String::from).collect(),
+        })
+    }
+
+    /// The transmutation configured with the arguments, which have to match its
+    /// parameters, e.g. the `["80"]` of `truncate 80`.
+    pub fn stage(&self, name: &str, arguments: &[String]) -> Result<Stage<'_>> {
+        let transmutation = self.find(name)?;
+        if arguments.is_empty() && transmutation.parameters().is_empty() {
+            return Ok(Stage::Registered(transmutation));
+        }
+        Ok(Stage::Configured(transmutation.with_arguments(arguments)?))
+    }
+
+    /// Parses a pipeline such as `lowercase|truncate 80|slugify`.
+    pub fn pipeline(&self, text: &str) -> Result<Pipeline<'_>> {
+        Pipeline::from_str(self, text)
+    }
+
+    /// Registered transmutations in the order they were registered.
+    pub fn transmutations(&self) -> impl Iterator<Item = &dyn Transmute> {
+        self.transmutations.iter().map(|t| t.as_ref())
+    }
+
+    pub fn all_variants(&self) -> Vec<&str> {
+        self.transmutations.iter().map(|t| t.name()).collect()
+    }
+
+    /// The usages of the transmutations, e.g. `[lowercase, truncate <width>]`.
+    pub fn stringify_possible_transmutations(&self) -> String {
+        let usages = self
+            .transmutations()
+            .map(|t| t.usage())
+            .collect::<Vec<String>>();
+        format!("[{}]", usages.join(", "))
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shout;
+
+    impl Transmute for Shout {
+        fn name(&self) -> &str {
+            "shout"
+        }
+
+        fn transmute(&self, input: &str) -> Result<String> {
+            Ok(format!("{}!", input.to_uppercase()))
+        }
+    }
+
+    #[test]
+    fn contains_built_in_transmutations() {
+        let registry = Registry::new();
+        assert_eq!(
+            registry.all_variants(),
+            vec![
+                "lowercase",
+                "uppercase",
+                "no-spaces",
+                "slugify",
+                "camelcase",
+                "reverse-talk",
+                "snake-case",
+                "kebab-case",
+                "screaming-snake-case",
+                "camel-case",
+                "pascal-case",
+                "title-case",
+                "base64-encode",
+                "base64-decode",
+                "base64url-encode",
+                "base64url-decode",
+                "base32-encode",
+                "base32-decode",
+                "hex-encode",
+                "hex-decode",
+                "url-encode",
+                "url-decode",
+                "html-escape",
+                "html-unescape",
+                "rot13",
+                "atbash",
+                "morse-encode",
+                "morse-decode",
+                "leetspeak",
+                "json-pretty",
+                "json-minify",
+                "json-to-yaml",
+                "json-to-toml",
+                "yaml-to-json",
+                "yaml-to-toml",
+                "toml-to-json",
+                "toml-to-yaml",
+                "csv-to-json",
+                "json-to-csv",
+                "sha256",
+                "sha512",
+                "sha1",
+                "md5",
+                "blake3",
+                "crc32",
+                "hmac-sha256",
+                "hmac-sha512",
+                "hmac-sha1",
+                "hmac-md5",
+                "stats",
+                "truncate",
+                "wrap",
+                "reflow",
+                "pad-left",
+                "pad-right",
+                "replace",
+                "regex-replace",
+                "repeat",
+                "caesar",
+                "vigenere-encrypt",
+                "vigenere-decrypt",
+                "markdown"
+            ]
+        );
+    }
+
+    #[test]
+    fn registering_custom_transmutation() {
+        let mut registry = Registry::new();
+        registry.register(Shout);
+
+        assert_eq!(registry.all_variants().last(), Some(&"shout"));
+        assert_eq!(registry.transmutations().last().unwrap().description(), "");
+        assert!(registry
+            .stringify_possible_transmutations()
+            .contains("shout"));
+        let result = registry.find("shout").unwrap().transmute("hey").unwrap();
+        assert_eq!(result, "HEY!");
+    }
+
+    #[test]
+    fn registering_replaces_same_name() {
+        struct Lowercase;
+        impl Transmute for Lowercase {
+            fn name(&self) -> &str {
+                "lowercase"
+            }
+
+            fn transmute(&self, _input: &str) -> Result<String> {
+                Ok("custom".to_string())
+            }
+        }
+
+        let mut registry = Registry::new();
+        let count = registry.all_variants().len();
+        registry.register(Lowercase);
+
+        assert_eq!(registry.all_variants().len(), count);
+        let result = registry.find("lowercase").unwrap().transmute("X").unwrap();
+        assert_eq!(result, "custom");
+    }
+
+    #[test]
+    fn registering_aliases() {
+        let mut registry = Registry::new();
+        let aliases = [Alias {
+            name: "ticket-slug".to_string(),
+            stages: vec![vec!["lowercase".to_string()], vec!["slugify".to_string()]],
+        }];
+        registry.register_aliases(&aliases).unwrap();
+
+        assert!(registry
+            .stringify_possible_transmutations()
+            .ends_with(", ticket-slug]"));
+        let result = registry
+            .pipeline("ticket-slug")
+            .unwrap()
+            .transmute("Crab Tank");
+        assert_eq!(result.unwrap(), "crab-tank");
+    }
+
+    #[test]
+    fn unknown_transmutation_lists_supported() {
+        let mut registry = Registry::new();
+        registry.register(Shout);
+
+        let err = registry.find("whisper").err().unwrap().to_string();
+        assert!(err.contains("whisper"));
+        assert!(err.contains("shout"));
+    }
+}
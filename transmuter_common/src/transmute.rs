@@ -0,0 +1,488 @@
+use std::io::{Read, Write};
+
+use crate::error::{Error, Result};
+
+use arguments::Parameter;
+use case::Case;
+use data::DataFormat;
+use encoding::Encoding;
+
+pub mod alias;
+pub mod arguments;
+pub mod case;
+pub mod cipher;
+pub mod csv;
+pub mod data;
+pub mod encoding;
+pub mod hash;
+pub mod markdown;
+pub mod parameterized;
+pub mod pipeline;
+pub mod reflow;
+pub mod stats;
+
+/// Text transformation which can be registered in the [`Registry`](crate::Registry).
+///
+/// Implement this trait to provide a custom transmutation; once registered it is
+/// listed next to the built-in ones in the help text and error messages.
+pub trait Transmute: Send + Sync {
+    /// Keyword used to invoke the transmutation, e.g. `reverse-talk`.
+    fn name(&self) -> &str;
+
+    /// One line summary shown in the help next to the name.
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// Parameters taken after the name, e.g. the `<width>` of `truncate 80`.
+    fn parameters(&self) -> &[Parameter] {
+        &[]
+    }
+
+    /// The transmutation configured with an argument for every parameter. The
+    /// arguments are validated, invalid ones fail with [`Error::InvalidArgument`].
+    fn with_arguments(&self, arguments: &[String]) -> Result<Box<dyn Transmute>> {
+        Err(Error::InvalidArgument(format!(
+            "The {} transmutation takes no arguments, provided: {}",
+            self.name(),
+            arguments.join(" ")
+        )))
+    }
+
+    /// The transmutation undoing this one, so transmuting its result gives the
+    /// original text back. Transmutations losing information, such as
+    /// `slugify`, have none.
+    fn inverse(&self) -> Option<Box<dyn Transmute>> {
+        None
+    }
+
+    /// Same as [`Transmute::inverse`], but fails with [`Error::InvalidArgument`]
+    /// for the transmutations which are not reversible.
+    fn invert(&self) -> Result<Box<dyn Transmute>> {
+        self.inverse().ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "The {} transmutation is not reversible",
+                self.name()
+            ))
+        })
+    }
+
+    /// The name followed by the parameters, e.g. `pad-left <width> <fill>`.
+    fn usage(&self) -> String {
+        arguments::usage(self.name(), self.parameters())
+    }
+
+    fn transmute(&self, input: &str) -> Result<String>;
+
+    /// Writes the result into the output. Transmutations producing large outputs
+    /// override it to write the result incrementally.
+    fn transmute_to(&self, input: &str, output: &mut dyn Write) -> Result<()> {
+        output.write_all(self.transmute(input)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Reads the whole input from the reader. Transmutations able to process
+    /// the input incrementally override it.
+    fn transmute_stream(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<()> {
+        self.transmute_to(&read_text(input)?, output)
+    }
+}
+
+/// Where the transmutations of whole files take the content from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    /// The input itself is the content.
+    Text,
+    /// The input is a path to the file, optionally preceded by the options of
+    /// the transmutation.
+    File,
+}
+
+/// Reads the whole input, reporting the offset of invalid UTF-8.
+pub(crate) fn read_text(input: &mut dyn Read) -> Result<String> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+macro_rules! transmutation_enum {
+    ($($variant:ident => $str:expr, $description:expr);*) => {
+
+        /// Built-in text transmutations available in every registry.
+        pub enum Transmutation {
+            $($variant,)*
+        }
+
+        impl Transmutation {
+
+            #[allow(clippy::should_implement_trait)]
+            pub fn from_str(text: &str) -> Option<Self> {
+                match text {
+                    $($str => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub fn all_variants() -> Vec<Self> {
+                vec![$(Self::$variant,)*]
+            }
+        }
+
+        impl Transmute for Transmutation {
+            fn name(&self) -> &str {
+                match self {
+                    $(Self::$variant => $str,)*
+                }
+            }
+
+            fn description(&self) -> &str {
+                match self {
+                    $(Self::$variant => $description,)*
+                }
+            }
+
+            fn inverse(&self) -> Option<Box<dyn Transmute>> {
+                self.inverse_variant()
+                    .map(|t| Box::new(t) as Box<dyn Transmute>)
+            }
+
+            fn transmute(&self, input: &str) -> Result<String> {
+                let result = match self {
+                    Self::Lowercase => input.to_lowercase(),
+                    Self::Uppercase => input.to_uppercase(),
+                    Self::NoSpaces => input.replace(' ', ""),
+                    Self::Slugify => slug::slugify(input),
+                    Self::Camelcase => to_camel_case(input),
+                    Self::PascalCase => Case::Pascal.convert(input),
+                    Self::SnakeCase => Case::Snake.convert(input),
+                    Self::KebabCase => Case::Kebab.convert(input),
+                    Self::ScreamingSnakeCase => Case::ScreamingSnake.convert(input),
+                    Self::CamelCase => Case::Camel.convert(input),
+                    Self::TitleCase => Case::Title.convert(input),
+                    Self::ReverseTalk => reverse_talk(input),
+                    Self::Base64Encode => Encoding::Base64.encode(input),
+                    Self::Base64Decode => Encoding::Base64.decode(input)?,
+                    Self::Base64UrlEncode => Encoding::Base64Url.encode(input),
+                    Self::Base64UrlDecode => Encoding::Base64Url.decode(input)?,
+                    Self::Base32Encode => Encoding::Base32.encode(input),
+                    Self::Base32Decode => Encoding::Base32.decode(input)?,
+                    Self::HexEncode => Encoding::Hex.encode(input),
+                    Self::HexDecode => Encoding::Hex.decode(input)?,
+                    Self::UrlEncode => Encoding::Percent.encode(input),
+                    Self::UrlDecode => Encoding::Percent.decode(input)?,
+                    Self::HtmlEscape => Encoding::Html.encode(input),
+                    Self::HtmlUnescape => Encoding::Html.decode(input)?,
+                    Self::Rot13 => cipher::caesar(input, 13),
+                    Self::Atbash => cipher::atbash(input),
+                    Self::MorseEncode => cipher::morse_encode(input)?,
+                    Self::MorseDecode => cipher::morse_decode(input)?,
+                    Self::Leetspeak => cipher::leetspeak(input),
+                    Self::JsonPretty => DataFormat::Json.convert(input, DataFormat::Json)?,
+                    Self::JsonMinify => data::minify_json(input)?,
+                    Self::JsonToYaml => DataFormat::Json.convert(input, DataFormat::Yaml)?,
+                    Self::JsonToToml => DataFormat::Json.convert(input, DataFormat::Toml)?,
+                    Self::YamlToJson => DataFormat::Yaml.convert(input, DataFormat::Json)?,
+                    Self::YamlToToml => DataFormat::Yaml.convert(input, DataFormat::Toml)?,
+                    Self::TomlToJson => DataFormat::Toml.convert(input, DataFormat::Json)?,
+                    Self::TomlToYaml => DataFormat::Toml.convert(input, DataFormat::Yaml)?,
+                    Self::CsvToJson => data::csv_to_json(input)?,
+                    Self::JsonToCsv => data::json_to_csv(input)?,
+                };
+
+                Ok(result)
+            }
+        }
+    };
+}
+
+impl Transmutation {
+    /// The encodings and their decodings undo each other, the ciphers and
+    /// `reverse-talk` undo themselves. Decoded text is encoded back into the
+    /// canonical form, e.g. base64 without line breaks.
+    fn inverse_variant(&self) -> Option<Self> {
+        let inverse = match self {
+            Self::ReverseTalk => Self::ReverseTalk,
+            Self::Base64Encode => Self::Base64Decode,
+            Self::Base64Decode => Self::Base64Encode,
+            Self::Base64UrlEncode => Self::Base64UrlDecode,
+            Self::Base64UrlDecode => Self::Base64UrlEncode,
+            Self::Base32Encode => Self::Base32Decode,
+            Self::Base32Decode => Self::Base32Encode,
+            Self::HexEncode => Self::HexDecode,
+            Self::HexDecode => Self::HexEncode,
+            Self::UrlEncode => Self::UrlDecode,
+            Self::UrlDecode => Self::UrlEncode,
+            Self::HtmlEscape => Self::HtmlUnescape,
+            Self::HtmlUnescape => Self::HtmlEscape,
+            Self::Rot13 => Self::Rot13,
+            Self::Atbash => Self::Atbash,
+            _ => return None,
+        };
+        Some(inverse)
+    }
+}
+
+/// Capitalizes the first letter of every word and removes the characters other
+/// than letters and digits, the rest of the word is kept as it is.
+fn to_camel_case(input: &str) -> String {
+    let mut result = String::new();
+    let mut capitalize_next = true;
+
+    for c in input.chars() {
+        if c.is_alphanumeric() {
+            if capitalize_next {
+                result.push(c.to_ascii_uppercase());
+                capitalize_next = false;
+            } else {
+                result.push(c);
+            }
+        } else {
+            capitalize_next = true;
+        }
+    }
+
+    result
+}
+
+/// Reverses the letters of every word, the whitespace between the words is
+/// kept, so reversing the result gives the text back.
+fn reverse_talk(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut word = Vec::new();
+    for c in input.chars() {
+        if c.is_whitespace() {
+            result.extend(word.drain(..).rev());
+            result.push(c);
+        } else {
+            word.push(c);
+        }
+    }
+    result.extend(word.into_iter().rev());
+    result
+}
+
+transmutation_enum!(
+    Lowercase => "lowercase", "Converts all letters to lowercase";
+    Uppercase => "uppercase", "Converts all letters to uppercase";
+    NoSpaces => "no-spaces", "Removes all spaces";
+    Slugify => "slugify", "Turns the text into a URL slug";
+    Camelcase => "camelcase", "Legacy PascalCase keeping acronyms and the other letters as they are, see pascal-case";
+    ReverseTalk => "reverse-talk", "Reverses the letters of every word";
+    SnakeCase => "snake-case", "Joins the words in snake_case";
+    KebabCase => "kebab-case", "Joins the words in kebab-case";
+    ScreamingSnakeCase => "screaming-snake-case", "Joins the words in SCREAMING_SNAKE_CASE";
+    CamelCase => "camel-case", "Joins the words in camelCase";
+    PascalCase => "pascal-case", "Joins the words in PascalCase";
+    TitleCase => "title-case", "Capitalizes the words and joins them with spaces";
+    Base64Encode => "base64-encode", "Encodes the text in base64";
+    Base64Decode => "base64-decode", "Decodes base64 into the text";
+    Base64UrlEncode => "base64url-encode", "Encodes the text in URL-safe base64 without padding";
+    Base64UrlDecode => "base64url-decode", "Decodes URL-safe base64 into the text";
+    Base32Encode => "base32-encode", "Encodes the text in base32";
+    Base32Decode => "base32-decode", "Decodes base32 into the text";
+    HexEncode => "hex-encode", "Encodes the bytes of the text as hex";
+    HexDecode => "hex-decode", "Decodes hex into the text";
+    UrlEncode => "url-encode", "Percent-encodes everything but the unreserved URL characters";
+    UrlDecode => "url-decode", "Decodes the percent-encoded text";
+    HtmlEscape => "html-escape", "Escapes the special HTML characters as entities";
+    HtmlUnescape => "html-unescape", "Replaces the HTML entities by their characters";
+    Rot13 => "rot13", "Rotates the letters by 13 places in the alphabet";
+    Atbash => "atbash", "Mirrors the letters in the alphabet";
+    MorseEncode => "morse-encode", "Encodes the text in Morse code";
+    MorseDecode => "morse-decode", "Decodes Morse code into the text";
+    Leetspeak => "leetspeak", "Replaces the letters resembling digits by them";
+    JsonPretty => "json-pretty", "Indents the JSON";
+    JsonMinify => "json-minify", "Removes the whitespace between the JSON tokens";
+    JsonToYaml => "json-to-yaml", "Converts JSON into YAML";
+    JsonToToml => "json-to-toml", "Converts a JSON object into TOML";
+    YamlToJson => "yaml-to-json", "Converts YAML into indented JSON";
+    YamlToToml => "yaml-to-toml", "Converts a YAML mapping into TOML";
+    TomlToJson => "toml-to-json", "Converts TOML into indented JSON";
+    TomlToYaml => "toml-to-yaml", "Converts TOML into YAML";
+    CsvToJson => "csv-to-json", "Converts CSV into a JSON array of objects keyed by the header";
+    JsonToCsv => "json-to-csv", "Converts a JSON array of objects into CSV"
+);
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{Transmutation, Transmute};
+    use crate::{error::Error, registry::Registry};
+
+    #[test]
+    fn lowercase_transmutation() {
+        let result = Transmutation::Lowercase
+            .transmute("Rust: Where 'match' is a date night for enums!")
+            .unwrap();
+        assert_eq!(result, "rust: where 'match' is a date night for enums!");
+    }
+
+    #[test]
+    fn uppercase_transmutation() {
+        let result = Transmutation::Uppercase
+            .transmute("Rust: Where lifetimes outlast relationships!")
+            .unwrap();
+        assert_eq!(result, "RUST: WHERE LIFETIMES OUTLAST RELATIONSHIPS!");
+    }
+
+    #[test]
+    fn no_space_transmutation() {
+        let result = Transmutation::NoSpaces
+            .transmute("Rust: Making pointers point less stressfully!")
+            .unwrap();
+        assert_eq!(result, "Rust:Makingpointerspointlessstressfully!");
+    }
+
+    #[test]
+    fn slugify_transmutation() {
+        let result = Transmutation::Slugify
+            .transmute("Rust: Where code 'borrows' but never forgets!")
+            .unwrap();
+        assert_eq!(result, "rust-where-code-borrows-but-never-forgets");
+    }
+
+    #[test]
+    fn camelcase_transmutation() {
+        let result = Transmutation::Camelcase.transmute(
+            "Why did the Rustacean start a band? Because they knew how to play the 'Result' harmoniously, with no 'panic' in the performance!",
+        ).unwrap();
+        assert_eq!(result, "WhyDidTheRustaceanStartABandBecauseTheyKnewHowToPlayTheResultHarmoniouslyWithNoPanicInThePerformance");
+        assert_eq!(
+            Transmutation::Camelcase
+                .transmute("HTTPServer config")
+                .unwrap(),
+            "HTTPServerConfig"
+        );
+    }
+
+    #[test]
+    fn case_transmutations() {
+        let input = "parseHTTPResponse for user_id";
+        let expected = [
+            (Transmutation::SnakeCase, "parse_http_response_for_user_id"),
+            (Transmutation::KebabCase, "parse-http-response-for-user-id"),
+            (
+                Transmutation::ScreamingSnakeCase,
+                "PARSE_HTTP_RESPONSE_FOR_USER_ID",
+            ),
+            (Transmutation::CamelCase, "parseHttpResponseForUserId"),
+            (Transmutation::PascalCase, "ParseHttpResponseForUserId"),
+            (Transmutation::TitleCase, "Parse Http Response For User Id"),
+        ];
+        for (transmutation, result) in expected {
+            assert_eq!(transmutation.transmute(input).unwrap(), result);
+        }
+    }
+
+    #[test]
+    fn streaming_invalid_utf8() {
+        let mut output = Vec::new();
+        let err = Transmutation::Uppercase
+            .transmute_stream(&mut &b"abc\xffdef"[..], &mut output)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidUtf8 { offset: Some(3) }));
+    }
+
+    #[test]
+    fn cipher_round_trips() {
+        let input = "Rust: Where 'unsafe' is just a secret handshake!";
+        let rot13 = Transmutation::Rot13.transmute(input).unwrap();
+        assert_eq!(rot13, "Ehfg: Jurer 'hafnsr' vf whfg n frperg unaqfunxr!");
+        assert_eq!(Transmutation::Rot13.transmute(&rot13).unwrap(), input);
+
+        let atbash = Transmutation::Atbash.transmute(input).unwrap();
+        assert_eq!(Transmutation::Atbash.transmute(&atbash).unwrap(), input);
+
+        let morse = Transmutation::MorseEncode.transmute(input).unwrap();
+        assert_eq!(
+            Transmutation::MorseDecode.transmute(&morse).unwrap(),
+            input.to_uppercase()
+        );
+    }
+
+    #[test]
+    fn data_transmutations() {
+        let input = "{\"crab\": {\"legs\": 10}}";
+        let pretty = Transmutation::JsonPretty.transmute(input).unwrap();
+        assert_eq!(pretty, "{\n  \"crab\": {\n    \"legs\": 10\n  }\n}");
+        assert_eq!(
+            Transmutation::JsonMinify.transmute(&pretty).unwrap(),
+            "{\"crab\":{\"legs\":10}}"
+        );
+        let toml = Transmutation::JsonToToml.transmute(input).unwrap();
+        assert_eq!(toml, "[crab]\nlegs = 10\n");
+        let err = Transmutation::TomlToYaml.transmute("[crab").unwrap_err();
+        assert_eq!(err.kind(), "parse");
+    }
+
+    #[test]
+    fn declaring_reversibility() {
+        let registry = Registry::new();
+        let reverse_talk = registry.find("reverse-talk").unwrap().invert().unwrap();
+        assert_eq!(
+            reverse_talk.transmute("tsuR  si\nnuf").unwrap(),
+            "Rust  is\nfun"
+        );
+        assert_eq!(
+            registry
+                .find("base64-encode")
+                .unwrap()
+                .invert()
+                .unwrap()
+                .name(),
+            "base64-decode"
+        );
+        let err = registry.find("slugify").unwrap().invert().err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "The slugify transmutation is not reversible"
+        );
+        assert!(registry.find("truncate").unwrap().inverse().is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn inverting_reversible_transmutations(text in any::<String>()) {
+            let registry = Registry::new();
+            for transmutation in registry.transmutations() {
+                let Some(inverse) = transmutation.inverse() else {
+                    continue;
+                };
+                // the decodings only take the text encoded by their inverse
+                let name = transmutation.name();
+                let input = match name.ends_with("-decode") || name == "html-unescape" {
+                    true => inverse.transmute(&text).unwrap(),
+                    false => text.clone(),
+                };
+                let output = transmutation.transmute(&input).unwrap();
+                prop_assert_eq!(inverse.transmute(&output).unwrap(), input, "{}", name);
+            }
+        }
+
+        #[test]
+        fn inverting_ciphers(text in any::<String>(), shift in any::<i64>(), key in "[a-zA-Z]{1,8}") {
+            let registry = Registry::new();
+            let stages = [
+                registry.stage("caesar", &[shift.to_string()]).unwrap(),
+                registry.stage("vigenere-encrypt", std::slice::from_ref(&key)).unwrap(),
+                registry.stage("vigenere-decrypt", &[key]).unwrap(),
+            ];
+            for stage in stages {
+                let output = stage.transmute(&text).unwrap();
+                prop_assert_eq!(stage.invert().unwrap().transmute(&output).unwrap(), text.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn reverse_talk_transmutation() {
+        let result = Transmutation::ReverseTalk
+            .transmute("Rust: Where 'match' is the only game that never ends!")
+            .unwrap();
+        assert_eq!(
+            result,
+            ":tsuR erehW 'hctam' si eht ylno emag taht reven !sdne"
+        );
+    }
+}
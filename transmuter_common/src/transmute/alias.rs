@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use crate::{
+    config::Alias,
+    error::{Error, Result},
+    registry::Registry,
+    transmute::{arguments::quote, Transmute},
+};
+
+/// Transmutation running the stages of an [`Alias`] defined by the user, its
+/// nested aliases are expanded into their stages.
+pub struct AliasTransmutation {
+    name: String,
+    description: String,
+    stages: Vec<(String, Arc<dyn Transmute>)>,
+}
+
+impl AliasTransmutation {
+    /// Expands the aliases into the transmutations of the registry. Stages which
+    /// are neither registered nor aliases, invalid arguments, aliases shadowing
+    /// a registered transmutation and aliases expanding into themselves fail.
+    pub fn resolve(registry: &Registry, aliases: &[Alias]) -> Result<Vec<Self>> {
+        aliases
+            .iter()
+            .map(|alias| {
+                if registry.get(&alias.name).is_some() {
+                    return Err(Error::InvalidArgument(format!(
+                        "The alias {0} shadows the {0} transmutation",
+                        alias.name
+                    )));
+                }
+                let mut stages = Vec::new();
+                expand(registry, aliases, alias, &mut vec![], &mut stages)?;
+                let pipeline = alias
+                    .stages
+                    .iter()
+                    .map(|stage| {
+                        stage
+                            .iter()
+                            .map(|s| quote(s))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<String>>()
+                    .join("|");
+                Ok(Self {
+                    name: alias.name.clone(),
+                    description: format!("Alias of {pipeline}"),
+                    stages,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Appends the transmutations of the alias to the stages. The path holds the
+/// names of the aliases being expanded, to report a cycle.
+fn expand(
+    registry: &Registry,
+    aliases: &[Alias],
+    alias: &Alias,
+    path: &mut Vec<String>,
+    stages: &mut Vec<(String, Arc<dyn Transmute>)>,
+) -> Result<()> {
+    path.push(alias.name.clone());
+    for stage in &alias.stages {
+        let (name, arguments) = (&stage[0], &stage[1..]);
+        if let Some(nested) = aliases.iter().find(|a| a.name == *name) {
+            // only the loop is reported, not the aliases leading into it
+            if let Some(start) = path.iter().position(|a| a == name) {
+                return Err(Error::InvalidArgument(format!(
+                    "The alias {name} expands into itself: {} -> {name}",
+                    path[start..].join(" -> ")
+                )));
+            }
+            if !arguments.is_empty() {
+                return Err(Error::InvalidArgument(format!(
+                    "The alias {name} takes no arguments, provided: {}",
+                    arguments.join(" ")
+                )));
+            }
+            expand(registry, aliases, nested, path, stages)?;
+            continue;
+        }
+
+        let transmutation = registry.shared(name).ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "The alias {} uses the unsupported transmutation: {name}",
+                alias.name
+            ))
+        })?;
+        let transmutation = match arguments.is_empty() && transmutation.parameters().is_empty() {
+            true => transmutation,
+            false => Arc::from(transmutation.with_arguments(arguments)?),
+        };
+        stages.push((name.clone(), transmutation));
+    }
+    path.pop();
+    Ok(())
+}
+
+impl Transmute for AliasTransmutation {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Aliases are reversible when all their stages are, the inverse runs the
+    /// inverses of the stages in the reverse order.
+    fn inverse(&self) -> Option<Box<dyn Transmute>> {
+        let stages = self
+            .stages
+            .iter()
+            .rev()
+            .map(|(_, stage)| {
+                let inverse = stage.inverse()?;
+                Some((inverse.name().to_string(), Arc::from(inverse)))
+            })
+            .collect::<Option<Vec<(String, Arc<dyn Transmute>)>>>()?;
+        Some(Box::new(Self {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            stages,
+        }))
+    }
+
+    /// Failures of an alias with several stages are reported with the stage.
+    fn transmute(&self, input: &str) -> Result<String> {
+        let mut text = input.to_string();
+        for (index, (name, stage)) in self.stages.iter().enumerate() {
+            text = stage
+                .transmute(&text)
+                .map_err(|err| match self.stages.len() {
+                    1 => err,
+                    _ => Error::Pipeline {
+                        stage: index + 1,
+                        name: name.clone(),
+                        source: Box::new(err),
+                    },
+                })?;
+        }
+
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+
+    use super::*;
+
+    fn resolve(config: &str) -> Result<Vec<AliasTransmutation>> {
+        let config = Config::from_str(config)?;
+        AliasTransmutation::resolve(&Registry::new(), &config.aliases)
+    }
+
+    #[test]
+    fn expanding_nested_aliases() {
+        let aliases = resolve(
+            r#"
+[aliases]
+ticket-slug = ["shout", "slugify"]
+shout = ["uppercase", "truncate 8"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(aliases[0].description(), "Alias of shout|slugify");
+        assert_eq!(
+            aliases[0].transmute("Fix the crab tank").unwrap(),
+            "fix-the"
+        );
+        assert_eq!(aliases[1].transmute("Fix the crab").unwrap(), "FIX THE ");
+    }
+
+    #[test]
+    fn inverting_aliases() {
+        let aliases = resolve(
+            "[aliases]\nhide = [\"rot13\", \"caesar 3\", \"base64-encode\"]\nshort = [\"rot13\", \"truncate 3\"]",
+        )
+        .unwrap();
+        let hidden = aliases[0].transmute("Ferris").unwrap();
+        let inverse = aliases[0].invert().unwrap();
+        assert_eq!(inverse.transmute(&hidden).unwrap(), "Ferris");
+        assert!(aliases[1].inverse().is_none());
+    }
+
+    #[test]
+    fn rejecting_invalid_aliases() {
+        let message = |config: &str| resolve(config).err().unwrap().to_string();
+        assert_eq!(
+            message("[aliases]\nslug = [\"lowercase\", \"shout\"]"),
+            "The alias slug uses the unsupported transmutation: shout"
+        );
+        assert_eq!(
+            message("[aliases]\na = [\"b\"]\nb = [\"lowercase\", \"a\"]"),
+            "The alias a expands into itself: a -> b -> a"
+        );
+        assert_eq!(
+            message("[aliases]\nc = [\"a\"]\na = [\"b\"]\nb = [\"lowercase\", \"a\"]"),
+            "The alias a expands into itself: a -> b -> a"
+        );
+        assert_eq!(
+            message("[aliases]\nlowercase = [\"uppercase\"]"),
+            "The alias lowercase shadows the lowercase transmutation"
+        );
+        assert!(resolve("[aliases]\nshort = [\"truncate\"]").is_err());
+    }
+}
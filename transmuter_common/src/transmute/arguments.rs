@@ -0,0 +1,284 @@
+use crate::error::{Error, Result};
+
+const STAGE_SEPARATOR: char = '|';
+
+/// Argument a transmutation takes after its name, e.g. the `<width>` of `truncate 80`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Parameter {
+    pub name: &'static str,
+    /// What the argument means, shown in the help.
+    pub description: &'static str,
+}
+
+impl Parameter {
+    pub const fn new(name: &'static str, description: &'static str) -> Self {
+        Self { name, description }
+    }
+
+    /// Parses the argument of a number parameter.
+    pub fn number(&self, transmutation: &str, argument: &str) -> Result<usize> {
+        argument
+            .parse()
+            .map_err(|_| self.invalid(transmutation, "a non-negative number", argument))
+    }
+
+    /// Parses the argument of a number parameter which cannot exceed the maximum.
+    pub fn bounded(&self, transmutation: &str, argument: &str, max: usize) -> Result<usize> {
+        match self.number(transmutation, argument)? {
+            number if number > max => {
+                Err(self.invalid(transmutation, &format!("a number up to {max}"), argument))
+            }
+            number => Ok(number),
+        }
+    }
+
+    /// Parses the argument of a number parameter which can be negative.
+    pub fn integer(&self, transmutation: &str, argument: &str) -> Result<i64> {
+        argument
+            .parse()
+            .map_err(|_| self.invalid(transmutation, "a number", argument))
+    }
+
+    /// Parses the argument of a character parameter.
+    pub fn character(&self, transmutation: &str, argument: &str) -> Result<char> {
+        let mut chars = argument.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(self.invalid(transmutation, "a single character", argument)),
+        }
+    }
+
+    /// Compiles the argument of a regex parameter.
+    pub fn regex(&self, transmutation: &str, argument: &str) -> Result<regex::Regex> {
+        regex::Regex::new(argument).map_err(|e| {
+            Error::InvalidArgument(format!(
+                "The {transmutation} argument <{}> is not a valid regular expression: {e}",
+                self.name
+            ))
+        })
+    }
+
+    fn invalid(&self, transmutation: &str, expected: &str, argument: &str) -> Error {
+        Error::InvalidArgument(format!(
+            "The {transmutation} argument <{}> requires {expected}, provided: {argument}",
+            self.name
+        ))
+    }
+}
+
+/// The name followed by the parameters, e.g. `pad-left <width> <fill>`.
+pub fn usage(name: &str, parameters: &[Parameter]) -> String {
+    parameters
+        .iter()
+        .fold(name.to_string(), |usage, p| format!("{usage} <{}>", p.name))
+}
+
+/// Parses the options of the transmutation in the `--name value` or
+/// `--name=value` form, passing each to `set`. Every argument has to be an option.
+pub fn parse_options(
+    transmutation: &str,
+    args: &[String],
+    mut set: impl FnMut(&str, &str) -> Result<()>,
+) -> Result<()> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let option = arg
+            .strip_prefix("--")
+            .ok_or(Error::InvalidArgument(format!(
+                "Unexpected {transmutation} argument provided: {arg}"
+            )))?;
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => (name, value),
+            None => (option, args.next().map_or("", |v| v.as_str())),
+        };
+        set_option(transmutation, name, value, &mut set)?;
+    }
+    Ok(())
+}
+
+/// Consumes the options at the beginning of the input, written the same way as
+/// by [`parse_options`], and returns the rest of the input. Values containing
+/// spaces are written in quotes, e.g. `--query 'where Age > 30'`.
+pub fn parse_leading_options<'a>(
+    transmutation: &str,
+    input: &'a str,
+    mut set: impl FnMut(&str, &str) -> Result<()>,
+) -> Result<&'a str> {
+    let mut rest = input.trim_start();
+    while let Some(option) = rest.strip_prefix("--") {
+        let (token, remainder) = split_token(option);
+        let (name, value, remainder) = match token.split_once('=') {
+            Some((name, value)) => (name, value, remainder),
+            None => {
+                let (value, remainder) = split_token(remainder.trim_start());
+                (token, value, remainder)
+            }
+        };
+        set_option(transmutation, name, value, &mut set)?;
+        rest = remainder.trim_start();
+    }
+    Ok(rest)
+}
+
+fn set_option(
+    transmutation: &str,
+    name: &str,
+    value: &str,
+    set: &mut impl FnMut(&str, &str) -> Result<()>,
+) -> Result<()> {
+    if value.is_empty() {
+        return Err(Error::InvalidArgument(format!(
+            "Missing value for the {transmutation} option --{name}"
+        )));
+    }
+    set(name, value)
+}
+
+/// Splits off the first whitespace separated token. A token starting with a
+/// quote lasts until the matching closing quote, which is not part of it.
+fn split_token(text: &str) -> (&str, &str) {
+    if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        if let Some(end) = text[1..].find(quote) {
+            return (&text[1..end + 1], &text[end + 2..]);
+        }
+    }
+    text.split_once(char::is_whitespace).unwrap_or((text, ""))
+}
+
+/// Splits the pipeline such as `lowercase|truncate 80|replace ' ' '-'` into its
+/// stages, each being the name followed by the arguments. Text in single or double
+/// quotes is kept together with its whitespace and `|`, backslashes are kept as
+/// they are, so regular expressions can be written without escaping them twice.
+pub fn split_stages(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut stages = Vec::new();
+    let mut stage = Vec::new();
+    let mut argument: Option<String> = None;
+    let mut quote = None;
+
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => argument.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                argument.get_or_insert_with(String::new);
+            }
+            (None, STAGE_SEPARATOR) => {
+                stage.extend(argument.take());
+                stages.push(std::mem::take(&mut stage));
+            }
+            (None, c) if c.is_whitespace() => stage.extend(argument.take()),
+            (None, c) => argument.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(q) = quote {
+        return Err(Error::InvalidArgument(format!(
+            "The pipeline contains an unclosed quote {q}: {text}"
+        )));
+    }
+    stage.extend(argument);
+    stages.push(stage);
+
+    if stages.iter().any(Vec::is_empty) {
+        return Err(Error::InvalidArgument(format!(
+            "The pipeline contains an empty stage: {text}"
+        )));
+    }
+    Ok(stages)
+}
+
+/// Quotes the argument for [`split_stages`] when it contains whitespace, quotes
+/// or the stage separator, or when it is empty.
+pub fn quote(argument: &str) -> String {
+    let special = |c: char| c.is_whitespace() || matches!(c, '"' | '\'' | STAGE_SEPARATOR);
+    if !argument.is_empty() && !argument.contains(special) {
+        return argument.to_string();
+    }
+    match argument.contains('\'') {
+        true => format!("\"{argument}\""),
+        false => format!("'{argument}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stages(text: &str) -> Vec<Vec<String>> {
+        split_stages(text).unwrap()
+    }
+
+    #[test]
+    fn splitting_stages() {
+        assert_eq!(
+            stages("lowercase | truncate 80|pad-left 10 0"),
+            vec![
+                vec!["lowercase"],
+                vec!["truncate", "80"],
+                vec!["pad-left", "10", "0"]
+            ]
+        );
+        assert_eq!(
+            stages(r"regex-replace '\d+' '#'|replace '|' '' "),
+            vec![vec!["regex-replace", r"\d+", "#"], vec!["replace", "|", ""]]
+        );
+    }
+
+    #[test]
+    fn rejecting_invalid_pipelines() {
+        assert!(split_stages("lowercase||slugify").is_err());
+        assert!(split_stages("replace 'a b").is_err());
+        assert!(split_stages("").is_err());
+    }
+
+    #[test]
+    fn parsing_options() {
+        let mut options = Vec::new();
+        let args = ["--format=json", "--top", "5"].map(String::from);
+        parse_options("stats", &args, |name, value| {
+            options.push(format!("{name}:{value}"));
+            Ok(())
+        })
+        .unwrap();
+        let rest = parse_leading_options("csv", "--query 'where a > 1' data.csv", |name, value| {
+            options.push(format!("{name}:{value}"));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(options, vec!["format:json", "top:5", "query:where a > 1"]);
+        assert_eq!(rest, "data.csv");
+
+        let ignore = |_: &str, _: &str| Ok(());
+        let err = parse_options("hash", &["--key".to_string()], ignore).unwrap_err();
+        assert_eq!(err.to_string(), "Missing value for the hash option --key");
+        let err = parse_options("stats", &["top".to_string()], ignore).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected stats argument provided: top");
+    }
+
+    #[test]
+    fn quoting_round_trip() {
+        let arguments = ["80", "", "a b", "it's", "|", r"\d+"];
+        let text = arguments.map(quote).join(" ");
+        assert_eq!(stages(&text), vec![arguments.to_vec()]);
+    }
+
+    #[test]
+    fn validating_arguments() {
+        let width = Parameter::new("width", "");
+        assert_eq!(width.number("truncate", "80").unwrap(), 80);
+        assert_eq!(width.integer("caesar", "-3").unwrap(), -3);
+        let err = width.number("truncate", "-1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The truncate argument <width> requires a non-negative number, provided: -1"
+        );
+
+        let fill = Parameter::new("fill", "");
+        assert_eq!(fill.character("pad-left", "ü").unwrap(), 'ü');
+        assert!(fill.character("pad-left", "ab").is_err());
+
+        let pattern = Parameter::new("pattern", "");
+        assert!(pattern.regex("regex-replace", "(").is_err());
+        assert_eq!(usage("pad-left", &[width, fill]), "pad-left <width> <fill>");
+    }
+}
@@ -0,0 +1,174 @@
+/// Identifier case the words of the text are joined in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Case {
+    /// `snake_case`
+    Snake,
+    /// `kebab-case`
+    Kebab,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+    /// `Title Case`
+    Title,
+}
+
+impl Case {
+    pub fn convert(&self, input: &str) -> String {
+        let words = words(input);
+        match self {
+            Self::Snake => join(&words, "_", str::to_lowercase),
+            Self::Kebab => join(&words, "-", str::to_lowercase),
+            Self::ScreamingSnake => join(&words, "_", str::to_uppercase),
+            Self::Pascal => join(&words, "", capitalize),
+            Self::Title => join(&words, " ", capitalize),
+            Self::Camel => match words.split_first() {
+                Some((first, rest)) => first.to_lowercase() + &join(rest, "", capitalize),
+                None => String::new(),
+            },
+        }
+    }
+}
+
+/// Splits the text into words. Besides the characters other than letters and
+/// digits, the words are separated by the humps of the camel case, so both
+/// `HTTPServer` and `http_server` consist of `HTTP`/`http` and `Server`/`server`.
+/// A capital letter starts a word after a lowercase letter or a digit, and after
+/// another capital letter when a lowercase one follows it. Digits never start a
+/// word, e.g. `utf8Decoder` is `utf8` and `Decoder`, `HTTP2Server` is `HTTP2` and
+/// `Server`.
+pub fn words(input: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+
+    for part in input.split(|c: char| !c.is_alphanumeric()) {
+        let chars = part.char_indices().collect::<Vec<(usize, char)>>();
+        let mut start = 0;
+        for (i, window) in chars.windows(2).enumerate() {
+            let [(_, previous), (index, current)] = window else {
+                continue;
+            };
+            let next = chars.get(i + 2).map(|(_, c)| *c);
+            let hump = current.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)));
+            if hump {
+                words.push(&part[start..*index]);
+                start = *index;
+            }
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+
+    words
+}
+
+fn join(words: &[&str], separator: &str, case: impl Fn(&str) -> String) -> String {
+    words
+        .iter()
+        .map(|word| case(word))
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+/// Uppercases the first letter and lowercases the rest of the word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.as_str().to_lowercase().chars())
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_CASES: [Case; 6] = [
+        Case::Snake,
+        Case::Kebab,
+        Case::ScreamingSnake,
+        Case::Camel,
+        Case::Pascal,
+        Case::Title,
+    ];
+
+    #[test]
+    fn splitting_words() {
+        assert_eq!(words("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(
+            words("parseHTTPResponse"),
+            vec!["parse", "HTTP", "Response"]
+        );
+        assert_eq!(words("XMLHttpRequest"), vec!["XML", "Http", "Request"]);
+        assert_eq!(words("utf8Decoder"), vec!["utf8", "Decoder"]);
+        assert_eq!(words("HTTP2Server"), vec!["HTTP2", "Server"]);
+        assert_eq!(words("version2Beta"), vec!["version2", "Beta"]);
+        assert_eq!(
+            words("  hello__world--again "),
+            vec!["hello", "world", "again"]
+        );
+        assert_eq!(words("žluťoučkýKůň"), vec!["žluťoučký", "Kůň"]);
+        assert_eq!(words("ÉcoleNormale"), vec!["École", "Normale"]);
+        assert!(words(" - ").is_empty());
+    }
+
+    #[test]
+    fn converting_acronyms() {
+        let input = "HTTPServer";
+        assert_eq!(Case::Snake.convert(input), "http_server");
+        assert_eq!(Case::Kebab.convert(input), "http-server");
+        assert_eq!(Case::ScreamingSnake.convert(input), "HTTP_SERVER");
+        assert_eq!(Case::Camel.convert(input), "httpServer");
+        assert_eq!(Case::Pascal.convert(input), "HttpServer");
+        assert_eq!(Case::Title.convert(input), "Http Server");
+    }
+
+    #[test]
+    fn converting_non_ascii() {
+        let input = "über straße ñandú";
+        assert_eq!(Case::ScreamingSnake.convert(input), "ÜBER_STRASSE_ÑANDÚ");
+        assert_eq!(Case::Camel.convert(input), "überStraßeÑandú");
+        assert_eq!(Case::Title.convert(input), "Über Straße Ñandú");
+    }
+
+    #[test]
+    fn converting_with_digits() {
+        assert_eq!(
+            Case::Snake.convert("base64 Encoder v2"),
+            "base64_encoder_v2"
+        );
+        assert_eq!(Case::Pascal.convert("base64_encoder_v2"), "Base64EncoderV2");
+        assert_eq!(Case::Kebab.convert("Base64EncoderV2"), "base64-encoder-v2");
+    }
+
+    #[test]
+    fn round_tripping_between_cases() {
+        let inputs = [
+            "http server",
+            "parse json response",
+            "base64 encoder v2",
+            "žluťoučký kůň úpěl",
+        ];
+        for input in inputs {
+            for from in ALL_CASES {
+                let converted = from.convert(input);
+                for to in ALL_CASES {
+                    assert_eq!(
+                        to.convert(&converted),
+                        to.convert(input),
+                        "{input}: {from:?} -> {to:?}"
+                    );
+                }
+                assert_eq!(Case::Snake.convert(&converted).replace('_', " "), input);
+            }
+        }
+    }
+}
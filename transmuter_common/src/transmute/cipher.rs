@@ -0,0 +1,232 @@
+use crate::error::{Error, Result};
+
+/// International Morse code of the letters, digits and punctuation.
+const MORSE: [(char, &str); 54] = [
+    ('A', ".-"),
+    ('B', "-..."),
+    ('C', "-.-."),
+    ('D', "-.."),
+    ('E', "."),
+    ('F', "..-."),
+    ('G', "--."),
+    ('H', "...."),
+    ('I', ".."),
+    ('J', ".---"),
+    ('K', "-.-"),
+    ('L', ".-.."),
+    ('M', "--"),
+    ('N', "-."),
+    ('O', "---"),
+    ('P', ".--."),
+    ('Q', "--.-"),
+    ('R', ".-."),
+    ('S', "..."),
+    ('T', "-"),
+    ('U', "..-"),
+    ('V', "...-"),
+    ('W', ".--"),
+    ('X', "-..-"),
+    ('Y', "-.--"),
+    ('Z', "--.."),
+    ('0', "-----"),
+    ('1', ".----"),
+    ('2', "..---"),
+    ('3', "...--"),
+    ('4', "....-"),
+    ('5', "....."),
+    ('6', "-...."),
+    ('7', "--..."),
+    ('8', "---.."),
+    ('9', "----."),
+    ('.', ".-.-.-"),
+    (',', "--..--"),
+    ('?', "..--.."),
+    ('\'', ".----."),
+    ('!', "-.-.--"),
+    ('/', "-..-."),
+    ('(', "-.--."),
+    (')', "-.--.-"),
+    ('&', ".-..."),
+    (':', "---..."),
+    (';', "-.-.-."),
+    ('=', "-...-"),
+    ('+', ".-.-."),
+    ('-', "-....-"),
+    ('_', "..--.-"),
+    ('"', ".-..-."),
+    ('$', "...-..-"),
+    ('@', ".--.-."),
+];
+
+/// Separator of the words in Morse code.
+const MORSE_WORD_SEPARATOR: &str = " / ";
+
+/// Shifts the ASCII letters by the number of positions in the alphabet, keeping
+/// their case. A negative shift moves them backwards, 13 is ROT13.
+pub fn caesar(text: &str, shift: i64) -> String {
+    let shift = shift.rem_euclid(26) as u8;
+    text.chars().map(|c| shift_letter(c, shift)).collect()
+}
+
+/// Mirrors the ASCII letters in the alphabet, `a` becomes `z`.
+pub fn atbash(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' => (b'z' - (c as u8 - b'a')) as char,
+            'A'..='Z' => (b'Z' - (c as u8 - b'A')) as char,
+            c => c,
+        })
+        .collect()
+}
+
+/// Shifts every ASCII letter by the next letter of the key, the other characters
+/// are kept and do not use up the key. The key has to consist of ASCII letters.
+pub fn vigenere(text: &str, key: &str, decrypt: bool) -> String {
+    let mut shifts = key
+        .bytes()
+        .map(|b| b.to_ascii_lowercase() - b'a')
+        .map(|shift| if decrypt { (26 - shift) % 26 } else { shift })
+        .cycle();
+    text.chars()
+        .map(|c| match c.is_ascii_alphabetic() {
+            true => shift_letter(c, shifts.next().unwrap_or(0)),
+            false => c,
+        })
+        .collect()
+}
+
+fn shift_letter(c: char, shift: u8) -> char {
+    let base = match c {
+        'a'..='z' => b'a',
+        'A'..='Z' => b'A',
+        c => return c,
+    };
+    (base + (c as u8 - base + shift) % 26) as char
+}
+
+/// Encodes the text in Morse code, the letters separated by a space and the
+/// words by ` / `. The letters are case insensitive, characters without a code
+/// are an error.
+pub fn morse_encode(text: &str) -> Result<String> {
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        let codes = word
+            .chars()
+            .map(|c| {
+                MORSE
+                    .iter()
+                    .find(|(letter, _)| *letter == c.to_ascii_uppercase())
+                    .map(|(_, code)| *code)
+                    .ok_or_else(|| {
+                        let offset = word.as_ptr() as usize - text.as_ptr() as usize;
+                        Error::InvalidArgument(format!(
+                            "The character {c:?} of the word at offset {offset} has no Morse code"
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<&str>>>()?;
+        words.push(codes.join(" "));
+    }
+    Ok(words.join(MORSE_WORD_SEPARATOR))
+}
+
+/// Decodes Morse code into uppercase text, the words are separated by `/`.
+pub fn morse_decode(text: &str) -> Result<String> {
+    let mut words = Vec::new();
+    for word in text.split('/') {
+        let letters = word
+            .split_whitespace()
+            .map(|code| {
+                MORSE
+                    .iter()
+                    .find(|(_, c)| *c == code)
+                    .map(|(letter, _)| *letter)
+                    .ok_or_else(|| Error::Decode {
+                        encoding: "Morse".to_string(),
+                        offset: code.as_ptr() as usize - text.as_ptr() as usize,
+                        message: format!("{code} is not a Morse code"),
+                    })
+            })
+            .collect::<Result<String>>()?;
+        if !letters.is_empty() {
+            words.push(letters);
+        }
+    }
+    Ok(words.join(" "))
+}
+
+/// Replaces the letters resembling digits by them.
+pub fn leetspeak(text: &str) -> String {
+    text.chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'a' => '4',
+            'e' => '3',
+            'i' | 'l' => '1',
+            'o' => '0',
+            's' => '5',
+            't' => '7',
+            'b' => '8',
+            'g' => '9',
+            _ => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Rust: Where 'Ferris' shifts 13 letters!\nÜber";
+
+    #[test]
+    fn caesar_round_trip() {
+        assert_eq!(caesar("Hello, World", 3), "Khoor, Zruog");
+        assert_eq!(caesar("abc xyz", -1), "zab wxy");
+        for shift in [1, 13, 25, 27, -40] {
+            assert_eq!(caesar(&caesar(TEXT, shift), -shift), TEXT);
+        }
+        assert_eq!(caesar(&caesar(TEXT, 13), 13), TEXT);
+    }
+
+    #[test]
+    fn atbash_round_trip() {
+        assert_eq!(atbash("Wizard"), "Draziw");
+        assert_eq!(atbash(&atbash(TEXT)), TEXT);
+    }
+
+    #[test]
+    fn vigenere_round_trip() {
+        assert_eq!(
+            vigenere("ATTACK at dawn!", "Lemon", false),
+            "LXFOPV ef rnhr!"
+        );
+        let encrypted = vigenere(TEXT, "crab", false);
+        assert_ne!(encrypted, TEXT);
+        assert_eq!(vigenere(&encrypted, "crab", true), TEXT);
+    }
+
+    #[test]
+    fn morse_round_trip() {
+        let encoded = morse_encode("SOS, help 42").unwrap();
+        assert_eq!(
+            encoded,
+            "... --- ... --..-- / .... . .-.. .--. / ....- ..---"
+        );
+        assert_eq!(morse_decode(&encoded).unwrap(), "SOS, HELP 42");
+        assert_eq!(morse_decode(" .- /  -... ").unwrap(), "A B");
+    }
+
+    #[test]
+    fn morse_errors() {
+        assert!(morse_encode("crab 🦀").is_err());
+        match morse_decode(".- ...---... -") {
+            Err(Error::Decode { offset, .. }) => assert_eq!(offset, 3),
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn leetspeak_transmutation() {
+        assert_eq!(leetspeak("Leet Speak is Big"), "1337 5p34k 15 819");
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io::{Read, Write},
+};
+
+use csv::{Error, StringRecord};
+
+use crate::{
+    error::Result,
+    transmute::{read_text, Source, Transmute},
+};
+
+/// Where the `csv` transmutation takes the CSV content from.
+pub use super::Source as CsvSource;
+pub use describe::ColumnType;
+pub use dialect::{CsvDialect, CsvTrim};
+pub use options::{CsvFormat, CsvOptions};
+pub use query::Query;
+pub use table::{ColumnAlignment, Overflow, TableOptions, TableStyle};
+
+mod describe;
+mod dialect;
+mod export;
+mod options;
+mod query;
+mod stream;
+mod table;
+
+pub struct Csv {
+    header: Row,
+    rows: Vec<Row>,
+}
+
+impl Csv {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Result<Self> {
+        Self::from_reader(input.as_bytes(), &CsvDialect::default())
+    }
+
+    pub fn from_file(file_path: &str) -> Result<Self> {
+        let file = File::open(file_path.trim())?;
+        Self::from_reader(file, &CsvDialect::default())
+    }
+
+    fn from_reader(reader: impl Read, dialect: &CsvDialect) -> Result<Self> {
+        let mut reader = dialect.reader(reader)?;
+
+        let header = CsvDialect::header(&mut reader)?;
+        let rows: std::result::Result<Vec<Row>, Error> = reader
+            .records()
+            .map(|record| {
+                let record = record?;
+                Ok(Row::from_record(&record))
+            })
+            .collect();
+
+        Ok(Self {
+            header,
+            rows: rows?,
+        })
+    }
+
+    /// The table is rendered with the default [`TableOptions`].
+    pub fn export(&self, format: CsvFormat) -> Result<String> {
+        match format {
+            CsvFormat::Table => Ok(self.to_string()),
+            format => self.export_rows(format),
+        }
+    }
+}
+
+impl Display for Csv {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.table(&TableOptions::default()))
+    }
+}
+
+#[derive(Clone)]
+struct Row {
+    cells: Vec<String>,
+}
+
+impl Row {
+    fn from_record(record: &StringRecord) -> Self {
+        Self {
+            cells: record.iter().map(|field| field.to_string()).collect(),
+        }
+    }
+}
+
+enum CsvOperation {
+    Render,
+    Describe,
+}
+
+/// The `csv` transmutation rendering the parsed CSV as a table or exporting it
+/// to one of the [`CsvFormat`]s. The `csv-describe` variant renders the
+/// statistics of the CSV columns instead.
+///
+/// When the CSV is read from a file, the options can precede the file path,
+/// e.g. `--format json examples/data.csv`.
+pub struct CsvTransmutation {
+    source: Source,
+    options: CsvOptions,
+    operation: CsvOperation,
+}
+
+impl CsvTransmutation {
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            options: CsvOptions::default(),
+            operation: CsvOperation::Render,
+        }
+    }
+
+    pub fn describe(source: Source) -> Self {
+        Self {
+            operation: CsvOperation::Describe,
+            ..Self::new(source)
+        }
+    }
+
+    pub fn with_options(mut self, options: CsvOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl CsvTransmutation {
+    /// Options preceding the file path override the configured ones.
+    fn open(&self, input: &str) -> Result<(File, CsvOptions)> {
+        let (options, file_path) = self.options.clone().parse_leading(input)?;
+        Ok((File::open(file_path.trim())?, options))
+    }
+
+    /// Streams the records when possible, otherwise reads the whole CSV first.
+    fn process(
+        &self,
+        reader: impl Read,
+        options: &CsvOptions,
+        output: &mut dyn Write,
+    ) -> Result<()> {
+        let streamable = matches!(self.operation, CsvOperation::Render)
+            && options.query.as_ref().is_none_or(|q| q.is_streamable());
+        if streamable {
+            return stream::stream(reader, output, options);
+        }
+
+        let result = self.render(Csv::from_reader(reader, &options.dialect)?, options)?;
+        output.write_all(result.as_bytes())?;
+        Ok(())
+    }
+
+    fn render(&self, csv: Csv, options: &CsvOptions) -> Result<String> {
+        let csv = match &options.query {
+            Some(query) => csv.query(query)?,
+            None => csv,
+        };
+        let csv = match self.operation {
+            CsvOperation::Render => csv,
+            CsvOperation::Describe => csv.describe(),
+        };
+        match options.format {
+            CsvFormat::Table => Ok(csv.table(&options.table)),
+            format => csv.export(format),
+        }
+    }
+}
+
+impl Transmute for CsvTransmutation {
+    fn name(&self) -> &str {
+        match self.operation {
+            CsvOperation::Render => "csv",
+            CsvOperation::Describe => "csv-describe",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.operation {
+            CsvOperation::Render => "Renders the CSV as a table or exports it with --format",
+            CsvOperation::Describe => "Summarizes the types and statistics of the CSV columns",
+        }
+    }
+
+    fn transmute(&self, input: &str) -> Result<String> {
+        match self.source {
+            Source::Text => self.render(
+                Csv::from_reader(input.as_bytes(), &self.options.dialect)?,
+                &self.options,
+            ),
+            Source::File => {
+                let (file, options) = self.open(input)?;
+                self.render(Csv::from_reader(file, &options.dialect)?, &options)
+            }
+        }
+    }
+
+    fn transmute_to(&self, input: &str, output: &mut dyn Write) -> Result<()> {
+        match self.source {
+            Source::Text => self.process(input.as_bytes(), &self.options, output),
+            Source::File => {
+                let (file, options) = self.open(input)?;
+                self.process(file, &options, output)
+            }
+        }
+    }
+
+    fn transmute_stream(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<()> {
+        match self.source {
+            Source::Text => self.process(input, &self.options, output),
+            Source::File => {
+                let file_path = read_text(input)?;
+                self.transmute_to(&file_path, output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let input = "Name,Age,Location,Occupation
+        John,30,New York,Engineer
+        Alice,25,Los Angeles,Teacher
+        Bob,35,Chicago,Doctor
+        Eva,28,San Francisco,Designer";
+
+        let csv = Csv::from_str(input).unwrap();
+        let mut input_iter = input.lines();
+        let expected_header_line = input_iter.next().unwrap();
+
+        assert_eq!(csv.header.cells.join(","), expected_header_line.to_string());
+
+        input_iter.zip(csv.rows).for_each(|(line, row)| {
+            assert_eq!(row.cells.join(","), line.to_string());
+        });
+    }
+
+    #[test]
+    fn parsing_invalid_content() {
+        let input = "Name,Age,Location,Occupation
+        John,30,New York,Engineer,Not Matching";
+
+        let csv = Csv::from_str(input);
+        assert!(csv.is_err());
+    }
+
+    #[test]
+    fn parsing_file() {
+        let file_path = "examples/data.csv";
+        let csv = Csv::from_file(file_path).unwrap();
+        assert_eq!(csv.header.cells.len(), 4);
+        assert_eq!(csv.rows.len(), 4);
+    }
+
+    #[test]
+    fn transmuting_file_with_options() {
+        let transmutation = CsvTransmutation::new(CsvSource::File);
+        let result = transmutation
+            .transmute("--format markdown examples/data.csv")
+            .unwrap();
+        assert!(result.starts_with("| Name | Age | Location | Occupation |"));
+    }
+
+    #[test]
+    fn transmuting_text_with_options() {
+        let options = CsvOptions {
+            format: CsvFormat::Tsv,
+            ..Default::default()
+        };
+        let transmutation = CsvTransmutation::new(CsvSource::Text).with_options(options);
+        let result = transmutation.transmute("a,b\n1,2").unwrap();
+        assert_eq!(result, "a\tb\n1\t2");
+    }
+
+    #[test]
+    fn transmuting_with_dialect() {
+        let options = CsvOptions {
+            format: CsvFormat::Tsv,
+            ..Default::default()
+        };
+        let (options, _) = options
+            .parse_leading("--delimiter ; --headers false --comment #")
+            .unwrap();
+        let transmutation = CsvTransmutation::new(CsvSource::Text).with_options(options);
+        let result = transmutation
+            .transmute("# generated\nJohn;30\n# skipped\nAlice;25")
+            .unwrap();
+        assert_eq!(result, "column1\tcolumn2\nJohn\t30\nAlice\t25");
+    }
+
+    #[test]
+    fn transmuting_with_sniffed_dialect() {
+        let (options, _) = CsvOptions {
+            format: CsvFormat::JsonLines,
+            ..Default::default()
+        }
+        .parse_leading("--dialect auto")
+        .unwrap();
+        let transmutation = CsvTransmutation::new(CsvSource::Text).with_options(options);
+        let mut output = Vec::new();
+        transmutation
+            .transmute_to("Name|City\nJohn|\"Paris|Texas\"\n", &mut output)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"Name":"John","City":"Paris|Texas"}"#
+        );
+    }
+
+    #[test]
+    fn transmuting_with_query() {
+        let transmutation = CsvTransmutation::new(CsvSource::File);
+        let result = transmutation
+            .transmute(
+                "--query 'select Name where Age < 30 order by Name' --format tsv examples/data.csv",
+            )
+            .unwrap();
+        assert_eq!(result, "Name\nAlice\nEva");
+    }
+
+    #[test]
+    fn transmuting_with_describe() {
+        let transmutation = CsvTransmutation::describe(CsvSource::File);
+        assert_eq!(transmutation.name(), "csv-describe");
+        let result = transmutation
+            .transmute("--format tsv examples/data.csv")
+            .unwrap();
+        assert!(result.starts_with("Column\tType\tCount"));
+        assert_eq!(result.lines().count(), 5);
+    }
+
+    #[test]
+    fn streaming_file() {
+        let transmutation = CsvTransmutation::new(CsvSource::File);
+        let mut output = Vec::new();
+        transmutation
+            .transmute_to(
+                "--format jsonl --query 'where Age > 30' examples/data.csv",
+                &mut output,
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"Name":"Bob","Age":"35","Location":"Chicago","Occupation":"Doctor"}"#
+        );
+    }
+
+    #[test]
+    fn streaming_text_falls_back_to_buffering_when_sorting() {
+        let options = CsvOptions {
+            format: CsvFormat::Tsv,
+            query: Some(Query::parse("order by n desc").unwrap()),
+            ..Default::default()
+        };
+        let transmutation = CsvTransmutation::new(CsvSource::Text).with_options(options);
+        let mut output = Vec::new();
+        transmutation
+            .transmute_stream(&mut "n\n1\n3\n2".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "n\n3\n2\n1");
+    }
+
+    #[test]
+    fn parsing_invalid_file() {
+        let file_path = "examples/invalid_data.csv";
+        let csv = Csv::from_file(file_path);
+        assert!(csv.is_err());
+    }
+}
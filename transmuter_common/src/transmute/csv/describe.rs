@@ -0,0 +1,271 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
+
+use super::{Csv, Row};
+
+const TOP_VALUES: usize = 3;
+const NULL_VALUES: [&str; 4] = ["", "null", "na", "n/a"];
+
+/// Type inferred from all non-null values of the column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Bool,
+    Date,
+    String,
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Date => "date",
+            Self::String => "string",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl ColumnType {
+    pub(super) fn infer<'a>(values: impl Iterator<Item = &'a str> + Clone) -> Self {
+        if values.clone().next().is_none() {
+            return Self::String;
+        }
+        [Self::Integer, Self::Float, Self::Bool, Self::Date]
+            .into_iter()
+            .find(|t| values.clone().all(|v| t.accepts(v)))
+            .unwrap_or(Self::String)
+    }
+
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            Self::Integer => value.parse::<i64>().is_ok(),
+            Self::Float => value.parse::<f64>().is_ok(),
+            Self::Bool => parse_bool(value).is_some(),
+            Self::Date => is_date(value),
+            Self::String => true,
+        }
+    }
+}
+
+impl Csv {
+    /// Summary with one row per column: inferred type, count of values and
+    /// nulls, distinct values, min/max, mean/median of the numeric columns and
+    /// the most frequent values of the text columns.
+    pub fn describe(&self) -> Csv {
+        let header = [
+            "Column", "Type", "Count", "Nulls", "Distinct", "Min", "Max", "Mean", "Median", "Top",
+        ];
+        let rows = self
+            .header
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(index, name)| self.describe_column(index, name.trim()))
+            .collect();
+
+        Csv {
+            header: Row {
+                cells: header.iter().map(|h| h.to_string()).collect(),
+            },
+            rows,
+        }
+    }
+
+    fn describe_column(&self, index: usize, name: &str) -> Row {
+        let cells = self
+            .rows
+            .iter()
+            .map(|row| row.cells.get(index).map_or("", |c| c.trim()));
+        let values = cells.clone().filter(|c| !is_null(c));
+        let column_type = ColumnType::infer(values.clone());
+
+        let mut frequencies: HashMap<&str, usize> = HashMap::new();
+        values
+            .clone()
+            .for_each(|v| *frequencies.entry(v).or_default() += 1);
+        let count = values.clone().count();
+
+        let (min, max, mean, median) = match column_type {
+            ColumnType::Integer | ColumnType::Float => {
+                let mut numbers = values
+                    .clone()
+                    .filter_map(|v| v.parse::<f64>().ok())
+                    .collect::<Vec<f64>>();
+                numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+                (
+                    numbers.first().map(|n| format_number(*n)),
+                    numbers.last().map(|n| format_number(*n)),
+                    Some(format_number(mean)),
+                    median(&numbers).map(format_number),
+                )
+            }
+            ColumnType::Bool => (None, None, None, None),
+            ColumnType::Date | ColumnType::String => (
+                values.clone().min().map(str::to_string),
+                values.clone().max().map(str::to_string),
+                None,
+                None,
+            ),
+        };
+        let top = match column_type {
+            ColumnType::Bool | ColumnType::String => Some(top_values(&frequencies)),
+            _ => None,
+        };
+
+        let missing = || "-".to_string();
+        Row {
+            cells: vec![
+                name.to_string(),
+                column_type.to_string(),
+                count.to_string(),
+                (cells.count() - count).to_string(),
+                frequencies.len().to_string(),
+                min.unwrap_or_else(missing),
+                max.unwrap_or_else(missing),
+                mean.unwrap_or_else(missing),
+                median.unwrap_or_else(missing),
+                top.filter(|t| !t.is_empty()).unwrap_or_else(missing),
+            ],
+        }
+    }
+}
+
+pub(super) fn is_null(value: &str) -> bool {
+    NULL_VALUES.contains(&value.to_ascii_lowercase().as_str())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// ISO 8601 calendar date, e.g. `2023-10-28`.
+fn is_date(value: &str) -> bool {
+    let parts = value.split('-').collect::<Vec<&str>>();
+    let [year, month, day] = parts[..] else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+fn median(sorted: &[f64]) -> Option<f64> {
+    let middle = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) / 2.0),
+        _ => Some(sorted[middle]),
+    }
+}
+
+fn top_values(frequencies: &HashMap<&str, usize>) -> String {
+    let mut frequencies = frequencies.iter().collect::<Vec<(&&str, &usize)>>();
+    frequencies.sort_by(|(a_value, a_count), (b_value, b_count)| {
+        b_count.cmp(a_count).then(a_value.cmp(b_value))
+    });
+    frequencies
+        .iter()
+        .take(TOP_VALUES)
+        .map(|(value, count)| format!("{value} ({count})"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Formats the number with at most 4 decimal places, omitting trailing zeros.
+fn format_number(number: f64) -> String {
+    let formatted = format!("{number:.4}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(input: &str) -> Vec<Vec<String>> {
+        Csv::from_str(input)
+            .unwrap()
+            .describe()
+            .rows
+            .into_iter()
+            .map(|r| r.cells)
+            .collect()
+    }
+
+    #[test]
+    fn inferring_types() {
+        let rows = describe(
+            "int,float,bool,date,text\n\
+             1,1.5,true,2023-01-31,a\n\
+             -2,3,FALSE,2024-02-29,2\n\
+             ,,,,",
+        );
+        let types = rows.iter().map(|r| r[1].as_str()).collect::<Vec<&str>>();
+        assert_eq!(types, vec!["integer", "float", "bool", "date", "string"]);
+    }
+
+    #[test]
+    fn invalid_dates_are_strings() {
+        let rows = describe("date\n2023-02-29\n2023-01-01");
+        assert_eq!(rows[0][1], "string");
+    }
+
+    #[test]
+    fn describing_example_data() {
+        let rows = Csv::from_file("examples/data.csv")
+            .unwrap()
+            .describe()
+            .rows
+            .into_iter()
+            .map(|r| r.cells)
+            .collect::<Vec<Vec<String>>>();
+
+        assert_eq!(
+            rows[1],
+            vec!["Age", "integer", "4", "0", "4", "25", "35", "29.5", "29", "-"]
+        );
+        assert_eq!(rows[0][1], "string");
+        assert_eq!(rows[0][5], "Alice");
+        assert_eq!(rows[0][6], "John");
+        assert_eq!(rows[0][9], "Alice (1), Bob (1), Eva (1)");
+    }
+
+    #[test]
+    fn counting_nulls_and_top_values() {
+        let rows = describe("city\nPrague\n\"\"\nBrno\nPrague\nNULL\nOstrava\nBrno\nPrague");
+        assert_eq!(rows[0][2], "6");
+        assert_eq!(rows[0][3], "2");
+        assert_eq!(rows[0][4], "3");
+        assert_eq!(rows[0][9], "Prague (3), Brno (2), Ostrava (1)");
+    }
+}
@@ -0,0 +1,347 @@
+use std::io::{Cursor, Read};
+
+use csv::{Reader, ReaderBuilder, Trim};
+use encoding_rs::Encoding;
+use encoding_rs_io::DecodeReaderBytesBuilder;
+
+use crate::error::{Error, Result};
+
+use super::Row;
+
+/// Size of the input sample the dialect is sniffed from.
+const SNIFF_SAMPLE_SIZE: usize = 8 * 1024;
+const DELIMITER_CANDIDATES: [u8; 5] = [b',', b';', b'\t', b'|', b':'];
+
+/// How the CSV content is written. The values which are not set explicitly
+/// are either sniffed from the beginning of the content, when `auto` is set,
+/// or taken from the standard comma separated format with a header.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsvDialect {
+    pub auto: bool,
+    pub delimiter: Option<u8>,
+    pub quote: Option<u8>,
+    pub escape: Option<u8>,
+    pub has_headers: Option<bool>,
+    pub comment: Option<u8>,
+    pub flexible: Option<bool>,
+    pub trim: Option<CsvTrim>,
+    /// Label of the content encoding, e.g. `latin1` or `utf-16le`. UTF-8 and the
+    /// encodings announced by a byte order mark are recognized without it.
+    pub encoding: Option<String>,
+}
+
+/// Whitespace trimming applied by the reader.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CsvTrim {
+    None,
+    Headers,
+    Fields,
+    All,
+}
+
+impl CsvTrim {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Option<Self> {
+        match text {
+            "none" => Some(Self::None),
+            "headers" => Some(Self::Headers),
+            "fields" => Some(Self::Fields),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    fn to_csv_trim(self) -> Trim {
+        match self {
+            Self::None => Trim::None,
+            Self::Headers => Trim::Headers,
+            Self::Fields => Trim::Fields,
+            Self::All => Trim::All,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Decodes the content and builds the CSV reader, sniffing the dialect first
+    /// when requested.
+    pub(super) fn reader<'a>(&self, reader: impl Read + 'a) -> Result<Reader<Box<dyn Read + 'a>>> {
+        let encoding = match &self.encoding {
+            Some(label) => Some(Encoding::for_label(label.as_bytes()).ok_or(
+                Error::InvalidArgument(format!("The unsupported csv encoding provided: {label}")),
+            )?),
+            None => None,
+        };
+        let mut reader: Box<dyn Read + 'a> = Box::new(
+            DecodeReaderBytesBuilder::new()
+                .encoding(encoding)
+                .build(reader),
+        );
+
+        let dialect = if self.auto {
+            let mut sample = Vec::with_capacity(SNIFF_SAMPLE_SIZE);
+            reader
+                .by_ref()
+                .take(SNIFF_SAMPLE_SIZE as u64)
+                .read_to_end(&mut sample)?;
+            let sniffed = Self::sniff(&sample);
+            reader = Box::new(Cursor::new(sample).chain(reader));
+            self.or(&sniffed)
+        } else {
+            self.clone()
+        };
+
+        let mut builder = ReaderBuilder::new();
+        builder
+            .delimiter(dialect.delimiter.unwrap_or(b','))
+            .quote(dialect.quote.unwrap_or(b'"'))
+            .has_headers(dialect.has_headers.unwrap_or(true))
+            .comment(dialect.comment)
+            .flexible(dialect.flexible.unwrap_or(false))
+            .trim(dialect.trim.unwrap_or(CsvTrim::None).to_csv_trim());
+        if dialect.escape.is_some() {
+            builder.escape(dialect.escape).double_quote(false);
+        }
+
+        Ok(builder.from_reader(reader))
+    }
+
+    /// Header of the CSV. Headerless content gets the columns named `column1`,
+    /// `column2` and so on.
+    pub(super) fn header<R: Read>(reader: &mut Reader<R>) -> Result<Row> {
+        let has_headers = reader.has_headers();
+        let header = reader.headers()?;
+        if has_headers {
+            return Ok(Row::from_record(header));
+        }
+        Ok(Row {
+            cells: (1..=header.len()).map(|i| format!("column{i}")).collect(),
+        })
+    }
+
+    /// Guesses the dialect from the beginning of the CSV content.
+    pub fn sniff(sample: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(sample);
+        let mut lines = text.lines().collect::<Vec<&str>>();
+        // the last line of a full sample is most likely cut in the middle
+        if sample.len() >= SNIFF_SAMPLE_SIZE && lines.len() > 1 {
+            lines.pop();
+        }
+
+        let comment = lines
+            .iter()
+            .any(|line| line.starts_with('#'))
+            .then_some(b'#');
+        let lines = lines
+            .into_iter()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .collect::<Vec<&str>>();
+
+        let quote = if count_outside_quotes(&lines.join("\n"), b'"', b'\'') == 0
+            && text.contains('\'')
+            && !text.contains('"')
+        {
+            b'\''
+        } else {
+            b'"'
+        };
+        let delimiter = sniff_delimiter(&lines, quote);
+        let rows = lines
+            .iter()
+            .map(|line| split_line(line, delimiter, quote))
+            .collect::<Vec<Vec<String>>>();
+        let flexible = rows.windows(2).any(|w| w[0].len() != w[1].len());
+
+        Self {
+            auto: false,
+            delimiter: Some(delimiter),
+            quote: Some(quote),
+            escape: None,
+            has_headers: Some(sniff_headers(&rows)),
+            comment,
+            flexible: Some(flexible),
+            trim: None,
+            encoding: None,
+        }
+    }
+
+    /// Fills the values which are not set from the other dialect.
+    fn or(&self, other: &Self) -> Self {
+        Self {
+            auto: self.auto,
+            delimiter: self.delimiter.or(other.delimiter),
+            quote: self.quote.or(other.quote),
+            escape: self.escape.or(other.escape),
+            has_headers: self.has_headers.or(other.has_headers),
+            comment: self.comment.or(other.comment),
+            flexible: self.flexible.or(other.flexible),
+            trim: self.trim.or(other.trim),
+            encoding: self.encoding.clone().or(other.encoding.clone()),
+        }
+    }
+}
+
+/// The delimiter occurring the same number of times on most of the lines wins.
+fn sniff_delimiter(lines: &[&str], quote: u8) -> u8 {
+    DELIMITER_CANDIDATES
+        .into_iter()
+        .map(|delimiter| {
+            let counts = lines
+                .iter()
+                .map(|line| count_outside_quotes(line, delimiter, quote))
+                .collect::<Vec<usize>>();
+            let mode = counts
+                .iter()
+                .copied()
+                .max_by_key(|count| counts.iter().filter(|c| *c == count).count())
+                .unwrap_or(0);
+            let consistent_lines = counts.iter().filter(|c| **c == mode).count();
+            (
+                delimiter,
+                mode,
+                if mode == 0 { 0 } else { consistent_lines },
+            )
+        })
+        .max_by(|(_, a_mode, a_lines), (_, b_mode, b_lines)| {
+            a_lines.cmp(b_lines).then(a_mode.cmp(b_mode))
+        })
+        .filter(|(_, mode, _)| *mode > 0)
+        .map_or(b',', |(delimiter, _, _)| delimiter)
+}
+
+/// The first row is a header when it holds text in the columns where the other
+/// rows hold numbers. Without any numeric column a header is assumed.
+fn sniff_headers(rows: &[Vec<String>]) -> bool {
+    let Some((first, rest)) = rows.split_first() else {
+        return true;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+
+    let votes = first
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            rest.iter().all(|row| {
+                row.get(*index)
+                    .is_some_and(|cell| cell.trim().parse::<f64>().is_ok())
+            })
+        })
+        .map(|(_, cell)| {
+            if cell.trim().parse::<f64>().is_ok() {
+                -1
+            } else {
+                1
+            }
+        })
+        .sum::<i32>();
+    votes >= 0
+}
+
+fn count_outside_quotes(line: &str, byte: u8, quote: u8) -> usize {
+    let mut quoted = false;
+    line.bytes()
+        .filter(|b| {
+            if *b == quote {
+                quoted = !quoted;
+            }
+            !quoted && *b == byte
+        })
+        .count()
+}
+
+fn split_line(line: &str, delimiter: u8, quote: u8) -> Vec<String> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .quote(quote)
+        .flexible(true)
+        .from_reader(line.as_bytes());
+    reader
+        .records()
+        .next()
+        .and_then(|record| record.ok())
+        .map(|record| record.iter().map(|f| f.to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffing_comma_separated() {
+        let dialect = CsvDialect::sniff(b"Name,Age\nJohn,30\nAlice,25\n");
+        assert_eq!(dialect.delimiter, Some(b','));
+        assert_eq!(dialect.has_headers, Some(true));
+        assert_eq!(dialect.comment, None);
+        assert_eq!(dialect.flexible, Some(false));
+    }
+
+    #[test]
+    fn sniffing_semicolon_separated_with_comments() {
+        let dialect = CsvDialect::sniff(
+            b"# exported 2023-10-28\nName;City;Age\n\"Doe, John\";Prague;30\nAlice;Brno;25\n",
+        );
+        assert_eq!(dialect.delimiter, Some(b';'));
+        assert_eq!(dialect.comment, Some(b'#'));
+        assert_eq!(dialect.has_headers, Some(true));
+    }
+
+    #[test]
+    fn sniffing_headerless_pipe_separated() {
+        let dialect = CsvDialect::sniff(b"John|30|1.5\nAlice|25|2.5\nBob|35|0.5\n");
+        assert_eq!(dialect.delimiter, Some(b'|'));
+        assert_eq!(dialect.has_headers, Some(false));
+    }
+
+    #[test]
+    fn sniffing_tab_separated() {
+        let dialect = CsvDialect::sniff(b"a\tb\tc\n1\t2\t3\n");
+        assert_eq!(dialect.delimiter, Some(b'\t'));
+    }
+
+    #[test]
+    fn explicit_values_override_sniffed() {
+        let dialect = CsvDialect {
+            auto: true,
+            has_headers: Some(true),
+            ..Default::default()
+        };
+        let mut reader = dialect.reader(&b"1;2\n3;4\n"[..]).unwrap();
+        let header = CsvDialect::header(&mut reader).unwrap();
+        assert_eq!(header.cells, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn reading_headerless() {
+        let dialect = CsvDialect {
+            has_headers: Some(false),
+            ..Default::default()
+        };
+        let mut reader = dialect.reader(&b"a,b\nc,d\n"[..]).unwrap();
+        let header = CsvDialect::header(&mut reader).unwrap();
+        assert_eq!(header.cells, vec!["column1", "column2"]);
+        assert_eq!(reader.records().count(), 2);
+    }
+
+    #[test]
+    fn reading_latin1() {
+        let dialect = CsvDialect {
+            encoding: Some("latin1".to_string()),
+            ..Default::default()
+        };
+        let mut reader = dialect.reader(&b"city\nK\xf6ln\n"[..]).unwrap();
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(&record[0], "Köln");
+    }
+
+    #[test]
+    fn unsupported_encoding() {
+        let dialect = CsvDialect {
+            encoding: Some("klingon".to_string()),
+            ..Default::default()
+        };
+        assert!(dialect.reader(&b""[..]).is_err());
+    }
+}
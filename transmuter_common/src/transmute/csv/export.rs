@@ -0,0 +1,297 @@
+use std::io::Write;
+
+use serde_json::{Map, Value};
+
+use crate::{error::Result, transmute::encoding::Encoding};
+
+use super::{Csv, CsvFormat, Row, TableOptions};
+
+impl Csv {
+    /// Renders all the rows in one of the formats other than the table.
+    pub(super) fn export_rows(&self, format: CsvFormat) -> Result<String> {
+        let mut output = Vec::new();
+        let mut writer = RowWriter::new(
+            format,
+            TableOptions::default(),
+            self.header.clone(),
+            None,
+            &mut output,
+        );
+        for row in &self.rows {
+            writer.write_row(row.clone())?;
+        }
+        writer.finish()?;
+
+        Ok(String::from_utf8(output)?)
+    }
+}
+
+/// Writes the rows into the output one by one as they come.
+///
+/// Only the table needs to know all its rows up front to size the columns, so
+/// the rows are rendered in tables of at most `window` rows. The other formats
+/// keep no rows in memory.
+pub(super) struct RowWriter<'a> {
+    format: CsvFormat,
+    table: TableOptions,
+    header: Row,
+    window: Option<usize>,
+    buffer: Vec<Row>,
+    output: &'a mut dyn Write,
+    rows_written: usize,
+    lines_written: usize,
+}
+
+impl<'a> RowWriter<'a> {
+    pub(super) fn new(
+        format: CsvFormat,
+        table: TableOptions,
+        header: Row,
+        window: Option<usize>,
+        output: &'a mut dyn Write,
+    ) -> Self {
+        Self {
+            format,
+            table,
+            header,
+            window,
+            buffer: Vec::new(),
+            output,
+            rows_written: 0,
+            lines_written: 0,
+        }
+    }
+
+    pub(super) fn write_row(&mut self, row: Row) -> Result<()> {
+        if self.rows_written == 0 {
+            self.write_start()?;
+        }
+
+        match self.format {
+            CsvFormat::Table => {
+                self.buffer.push(row);
+                if self.window.is_some_and(|w| self.buffer.len() >= w) {
+                    self.flush_table()?;
+                }
+            }
+            CsvFormat::Json => {
+                let object = serde_json::to_string_pretty(&self.json_object(&row))?;
+                let separator = if self.rows_written == 0 { "" } else { "," };
+                self.output.write_all(separator.as_bytes())?;
+                for line in object.lines() {
+                    self.line(&format!("  {line}"))?;
+                }
+            }
+            CsvFormat::JsonLines => {
+                let object = serde_json::to_string(&self.json_object(&row))?;
+                self.line(&object)?;
+            }
+            CsvFormat::Markdown => self.line(&markdown_line(&row))?,
+            CsvFormat::Html => self.line(&format!("    {}", html_row(&row, "td")))?,
+            CsvFormat::Tsv => self.line(&tsv_line(&row))?,
+        }
+
+        self.rows_written += 1;
+        Ok(())
+    }
+
+    pub(super) fn finish(mut self) -> Result<()> {
+        if self.rows_written == 0 {
+            self.write_start()?;
+        }
+
+        match self.format {
+            CsvFormat::Table => self.flush_table()?,
+            CsvFormat::Json if self.rows_written == 0 => self.output.write_all(b"]")?,
+            CsvFormat::Json => self.line("]")?,
+            CsvFormat::Html => {
+                self.line("  </tbody>")?;
+                self.line("</table>")?;
+            }
+            CsvFormat::JsonLines | CsvFormat::Markdown | CsvFormat::Tsv => {}
+        }
+
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn write_start(&mut self) -> Result<()> {
+        match self.format {
+            CsvFormat::Table | CsvFormat::JsonLines => {}
+            CsvFormat::Json => self.line("[")?,
+            CsvFormat::Markdown => {
+                let separator = Row {
+                    cells: self
+                        .header
+                        .cells
+                        .iter()
+                        .map(|_| "---".to_string())
+                        .collect(),
+                };
+                self.line(&markdown_line(&self.header.clone()))?;
+                self.line(&markdown_line(&separator))?;
+            }
+            CsvFormat::Html => {
+                let header = html_row(&self.header, "th");
+                self.line("<table>")?;
+                self.line("  <thead>")?;
+                self.line(&format!("    {header}"))?;
+                self.line("  </thead>")?;
+                self.line("  <tbody>")?;
+            }
+            CsvFormat::Tsv => self.line(&tsv_line(&self.header.clone()))?,
+        }
+
+        Ok(())
+    }
+
+    fn flush_table(&mut self) -> Result<()> {
+        if self.buffer.is_empty() && self.lines_written > 0 {
+            return Ok(());
+        }
+        let table = Csv {
+            header: self.header.clone(),
+            rows: std::mem::take(&mut self.buffer),
+        };
+        self.line(&table.table(&self.table))
+    }
+
+    /// Writes the text on a new line. No line break follows the last line.
+    fn line(&mut self, text: &str) -> Result<()> {
+        if self.lines_written > 0 {
+            self.output.write_all(b"\n")?;
+        }
+        self.output.write_all(text.as_bytes())?;
+        self.lines_written += 1;
+        Ok(())
+    }
+
+    fn json_object(&self, row: &Row) -> Value {
+        let object = self
+            .header
+            .cells
+            .iter()
+            .zip(row.cells.iter())
+            .map(|(key, value)| (key.trim().to_string(), Value::from(value.trim())))
+            .collect::<Map<String, Value>>();
+        Value::Object(object)
+    }
+}
+
+/// Row of the GitHub flavored Markdown table.
+fn markdown_line(row: &Row) -> String {
+    let cells = row
+        .cells
+        .iter()
+        .map(|cell| cell.trim().replace('|', "\\|"))
+        .collect::<Vec<String>>();
+    format!("| {} |", cells.join(" | "))
+}
+
+fn html_row(row: &Row, tag: &str) -> String {
+    let cells = row
+        .cells
+        .iter()
+        .map(|cell| format!("<{tag}>{}</{tag}>", Encoding::Html.encode(cell.trim())))
+        .collect::<String>();
+    format!("<tr>{cells}</tr>")
+}
+
+/// Tab separated values. Tabs and line breaks within the cells are escaped.
+fn tsv_line(row: &Row) -> String {
+    row.cells
+        .iter()
+        .map(|cell| escape_tsv(cell.trim()))
+        .collect::<Vec<String>>()
+        .join("\t")
+}
+
+fn escape_tsv(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transmute::csv::{Csv, CsvFormat};
+
+    fn data() -> Csv {
+        Csv::from_file("examples/data.csv").unwrap()
+    }
+
+    #[test]
+    fn exporting_json() {
+        let json = data().export(CsvFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 4);
+        assert_eq!(value[0]["Name"], "John");
+        assert_eq!(value[3]["Location"], "San Francisco");
+        assert!(json.find("\"Name\"").unwrap() < json.find("\"Occupation\"").unwrap());
+
+        let expected = serde_json::to_string_pretty(&value).unwrap();
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn exporting_empty_json() {
+        let csv = Csv::from_str("a,b").unwrap();
+        assert_eq!(csv.export(CsvFormat::Json).unwrap(), "[]");
+    }
+
+    #[test]
+    fn exporting_json_lines() {
+        let jsonl = data().export(CsvFormat::JsonLines).unwrap();
+        let lines = jsonl.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[2],
+            r#"{"Name":"Bob","Age":"35","Location":"Chicago","Occupation":"Doctor"}"#
+        );
+    }
+
+    #[test]
+    fn exporting_markdown() {
+        let markdown = data().export(CsvFormat::Markdown).unwrap();
+        let lines = markdown.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "| Name | Age | Location | Occupation |");
+        assert_eq!(lines[1], "| --- | --- | --- | --- |");
+        assert_eq!(lines[2], "| John | 30 | New York | Engineer |");
+        assert_eq!(lines.len(), 6);
+    }
+
+    #[test]
+    fn exporting_markdown_escapes_pipes() {
+        let csv = Csv::from_str("a,b\n\"x|y\",z").unwrap();
+        let markdown = csv.export(CsvFormat::Markdown).unwrap();
+        assert!(markdown.ends_with("| x\\|y | z |"));
+    }
+
+    #[test]
+    fn exporting_html() {
+        let html = data().export(CsvFormat::Html).unwrap();
+        assert!(html.starts_with("<table>"));
+        assert!(
+            html.contains("<tr><th>Name</th><th>Age</th><th>Location</th><th>Occupation</th></tr>")
+        );
+        assert!(html
+            .contains("<tr><td>Eva</td><td>28</td><td>San Francisco</td><td>Designer</td></tr>"));
+        assert!(html.ends_with("</table>"));
+    }
+
+    #[test]
+    fn exporting_html_escapes_markup() {
+        let csv = Csv::from_str("a\n<b>&</b>").unwrap();
+        let html = csv.export(CsvFormat::Html).unwrap();
+        assert!(html.contains("<td>&lt;b&gt;&amp;&lt;/b&gt;</td>"));
+    }
+
+    #[test]
+    fn exporting_tsv() {
+        let tsv = data().export(CsvFormat::Tsv).unwrap();
+        let lines = tsv.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "Name\tAge\tLocation\tOccupation");
+        assert_eq!(lines[4], "Eva\t28\tSan Francisco\tDesigner");
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    error::{Error, Result},
+    transmute::arguments::{parse_leading_options, parse_options},
+};
+
+use super::{
+    dialect::CsvTrim, query::Query, ColumnAlignment, CsvDialect, Overflow, TableOptions, TableStyle,
+};
+
+/// Output format of the `csv` transmutation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CsvFormat {
+    Table,
+    Json,
+    JsonLines,
+    Markdown,
+    Html,
+    Tsv,
+}
+
+impl CsvFormat {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Option<Self> {
+        match text {
+            "table" => Some(Self::Table),
+            "json" => Some(Self::Json),
+            "jsonl" => Some(Self::JsonLines),
+            "markdown" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+
+    pub fn all_variants() -> Vec<&'static str> {
+        vec!["table", "json", "jsonl", "markdown", "html", "tsv"]
+    }
+}
+
+/// Options of the `csv` transmutation provided in the `--name value` or
+/// `--name=value` form, e.g. `--format json`. Values containing spaces are
+/// written in quotes, e.g. `--query 'where Age > 30'`.
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    pub format: CsvFormat,
+    pub query: Option<Query>,
+    /// Maximum number of rows kept in memory for a table rendered while streaming.
+    pub window: usize,
+    pub dialect: CsvDialect,
+    pub table: TableOptions,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            format: CsvFormat::Table,
+            query: None,
+            window: 1000,
+            dialect: CsvDialect::default(),
+            table: TableOptions::default(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Builds the options from CLI arguments. Every argument has to be an option.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut options = Self::default();
+        parse_options("csv", args, |name, value| options.set(name, value))?;
+        Ok(options)
+    }
+
+    /// Consumes the options at the beginning of the input, overriding the current
+    /// values. Returns the updated options and the rest of the input.
+    pub fn parse_leading(mut self, input: &str) -> Result<(Self, &str)> {
+        let rest = parse_leading_options("csv", input, |name, value| self.set(name, value))?;
+        Ok((self, rest))
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "format" => {
+                self.format = CsvFormat::from_str(value).ok_or(Error::InvalidArgument(format!(
+                    "The unsupported csv format provided: {value}.\nFollowing are supported: [{}]",
+                    CsvFormat::all_variants().join(", ")
+                )))?;
+            }
+            "query" => self.query = Some(Query::parse(value)?),
+            "window" => {
+                self.window = value.parse::<usize>().ok().filter(|w| *w > 0).ok_or(
+                    Error::InvalidArgument(format!(
+                        "The csv option --window requires a positive number, provided: {value}"
+                    )),
+                )?;
+            }
+            "dialect" if value == "auto" => self.dialect.auto = true,
+            "dialect" => {
+                return Err(Error::InvalidArgument(format!(
+                "The unsupported csv dialect provided: {value}.\nFollowing are supported: [auto]"
+            )))
+            }
+            "delimiter" => self.dialect.delimiter = Some(parse_byte(name, value)?),
+            "quote" => self.dialect.quote = Some(parse_byte(name, value)?),
+            "escape" => self.dialect.escape = Some(parse_byte(name, value)?),
+            "comment" => self.dialect.comment = Some(parse_byte(name, value)?),
+            "headers" => self.dialect.has_headers = Some(parse_bool(name, value)?),
+            "flexible" => self.dialect.flexible = Some(parse_bool(name, value)?),
+            "trim" => {
+                self.dialect.trim = Some(CsvTrim::from_str(value).ok_or(Error::InvalidArgument(
+                    format!(
+                        "The unsupported csv trim provided: {value}.\nFollowing are supported: [none, headers, fields, all]"
+                    ),
+                ))?);
+            }
+            "encoding" => self.dialect.encoding = Some(value.to_string()),
+            "style" => {
+                self.table.style =
+                    TableStyle::from_str(value).ok_or(Error::InvalidArgument(format!(
+                    "The unsupported csv style provided: {value}.\nFollowing are supported: [{}]",
+                    TableStyle::all_variants().join(", ")
+                )))?;
+            }
+            "align" => {
+                self.table.alignments = value
+                    .split(',')
+                    .map(|alignment| {
+                        ColumnAlignment::from_str(alignment.trim()).ok_or(Error::InvalidArgument(
+                            format!(
+                                "The unsupported csv alignment provided: {alignment}.\nFollowing are supported: [{}]",
+                                ColumnAlignment::all_variants().join(", ")
+                            ),
+                        ))
+                    })
+                    .collect::<Result<Vec<ColumnAlignment>>>()?;
+            }
+            "max-width" => {
+                self.table.max_width = Some(value.parse::<usize>().ok().filter(|w| *w > 0).ok_or(
+                    Error::InvalidArgument(format!(
+                        "The csv option --max-width requires a positive number, provided: {value}"
+                    )),
+                )?);
+            }
+            "overflow" => {
+                self.table.overflow = Overflow::from_str(value).ok_or(Error::InvalidArgument(
+                    format!(
+                        "The unsupported csv overflow provided: {value}.\nFollowing are supported: [{}]",
+                        Overflow::all_variants().join(", ")
+                    ),
+                ))?;
+            }
+            "fit" => self.table.fit_terminal = parse_bool(name, value)?,
+            "crab" => self.table.crab_row = parse_bool(name, value)?,
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "The unsupported csv option provided: --{name}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Single ASCII character. The tab can be written as `tab` or `\t` too.
+fn parse_byte(name: &str, value: &str) -> Result<u8> {
+    match value {
+        "tab" | "\\t" => Ok(b'\t'),
+        _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+        _ => Err(Error::InvalidArgument(format!(
+            "The csv option --{name} requires a single ASCII character, provided: {value}"
+        ))),
+    }
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(Error::InvalidArgument(format!(
+            "The csv option --{name} requires true or false, provided: {value}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_leading_options() {
+        let (options, rest) = CsvOptions::default()
+            .parse_leading("--format json examples/data.csv")
+            .unwrap();
+        assert_eq!(options.format, CsvFormat::Json);
+        assert_eq!(rest, "examples/data.csv");
+
+        let (options, rest) = CsvOptions::default()
+            .parse_leading("--format=tsv examples/data.csv")
+            .unwrap();
+        assert_eq!(options.format, CsvFormat::Tsv);
+        assert_eq!(rest, "examples/data.csv");
+    }
+
+    #[test]
+    fn parsing_quoted_values() {
+        let (options, rest) = CsvOptions::default()
+            .parse_leading(r#"--query 'where Location = "Chicago"' --format=json data.csv"#)
+            .unwrap();
+        assert!(options.query.is_some());
+        assert_eq!(options.format, CsvFormat::Json);
+        assert_eq!(rest, "data.csv");
+    }
+
+    #[test]
+    fn parsing_args() {
+        let args = ["--query", "select Name where Age > 30", "--format=tsv"]
+            .map(String::from)
+            .to_vec();
+        let options = CsvOptions::from_args(&args).unwrap();
+        assert!(options.query.is_some());
+        assert_eq!(options.format, CsvFormat::Tsv);
+    }
+
+    #[test]
+    fn parsing_dialect_options() {
+        let (options, rest) = CsvOptions::default()
+            .parse_leading(
+                r#"--delimiter ";" --quote "'" --headers false --comment # --trim all --encoding latin1 data.csv"#,
+            )
+            .unwrap();
+        let dialect = options.dialect;
+        assert_eq!(dialect.delimiter, Some(b';'));
+        assert_eq!(dialect.quote, Some(b'\''));
+        assert_eq!(dialect.has_headers, Some(false));
+        assert_eq!(dialect.comment, Some(b'#'));
+        assert_eq!(dialect.trim, Some(CsvTrim::All));
+        assert_eq!(dialect.encoding.as_deref(), Some("latin1"));
+        assert_eq!(rest, "data.csv");
+
+        let (options, _) = CsvOptions::default()
+            .parse_leading("--dialect auto --delimiter tab data.csv")
+            .unwrap();
+        assert!(options.dialect.auto);
+        assert_eq!(options.dialect.delimiter, Some(b'\t'));
+    }
+
+    #[test]
+    fn parsing_table_options() {
+        let (options, rest) = CsvOptions::default()
+            .parse_leading(
+                "--style unicode --align left,right,center --max-width 20 --overflow wrap --fit true --crab false data.csv",
+            )
+            .unwrap();
+        let table = options.table;
+        assert_eq!(table.style, TableStyle::Unicode);
+        assert_eq!(
+            table.alignments,
+            vec![
+                ColumnAlignment::Left,
+                ColumnAlignment::Right,
+                ColumnAlignment::Center
+            ]
+        );
+        assert_eq!(table.max_width, Some(20));
+        assert_eq!(table.overflow, Overflow::Wrap);
+        assert!(table.fit_terminal);
+        assert!(!table.crab_row);
+        assert_eq!(rest, "data.csv");
+
+        assert!(CsvOptions::default()
+            .parse_leading("--style fancy a.csv")
+            .is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--align left,diagonal a.csv")
+            .is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--max-width 0 a.csv")
+            .is_err());
+    }
+
+    #[test]
+    fn parsing_without_options() {
+        let (options, rest) = CsvOptions::default()
+            .parse_leading("examples/data.csv")
+            .unwrap();
+        assert_eq!(options.format, CsvFormat::Table);
+        assert_eq!(rest, "examples/data.csv");
+    }
+
+    #[test]
+    fn parsing_invalid_options() {
+        assert!(CsvOptions::default()
+            .parse_leading("--format xml a.csv")
+            .is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--colour red a.csv")
+            .is_err());
+        assert!(CsvOptions::default().parse_leading("--format").is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--window 0 a.csv")
+            .is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--delimiter ;; a.csv")
+            .is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--headers yes a.csv")
+            .is_err());
+        assert!(CsvOptions::default()
+            .parse_leading("--dialect excel a.csv")
+            .is_err());
+        assert!(CsvOptions::from_args(&["--format".into(), "json".into(), "x".into()]).is_err());
+    }
+}
@@ -0,0 +1,621 @@
+use std::{cmp::Ordering, iter::Peekable, str::Chars};
+
+use crate::error::{Error, Result};
+
+use super::{Csv, Row};
+
+/// Query selecting, filtering, sorting and paginating the CSV rows, e.g.
+/// `select Name, Age where Age > 30 and Location = "Chicago" order by Age desc limit 10 offset 5`.
+///
+/// Every clause is optional but they have to follow the order above. Keywords
+/// are case-insensitive. Column names containing spaces are written in quotes.
+/// Numeric and boolean literals are compared as numbers and booleans, the
+/// quoted ones as text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    columns: Option<Vec<String>>,
+    filter: Option<Condition>,
+    order_by: Vec<SortKey>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    Comparison {
+        column: String,
+        operator: Operator,
+        value: Literal,
+    },
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Literal {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct SortKey {
+    column: String,
+    descending: bool,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self> {
+        Parser::new(tokenize(text)?).parse_query()
+    }
+
+    /// Sorting needs all the rows, the other clauses can be applied row by row.
+    pub(super) fn is_streamable(&self) -> bool {
+        self.order_by.is_empty()
+    }
+
+    /// Resolves the column names against the CSV header.
+    pub(super) fn resolve(&self, header: &Row) -> Result<ResolvedQuery> {
+        let columns = match &self.columns {
+            Some(columns) => columns
+                .iter()
+                .map(|c| column_index(header, c))
+                .collect::<Result<Vec<usize>>>()?,
+            None => (0..header.cells.len()).collect(),
+        };
+        let filter = self
+            .filter
+            .as_ref()
+            .map(|f| resolve_condition(header, f))
+            .transpose()?;
+        let order_by = self
+            .order_by
+            .iter()
+            .map(|key| Ok((column_index(header, &key.column)?, key.descending)))
+            .collect::<Result<Vec<(usize, bool)>>>()?;
+
+        Ok(ResolvedQuery {
+            columns,
+            filter,
+            order_by,
+            offset: self.offset,
+            limit: self.limit,
+        })
+    }
+}
+
+impl Csv {
+    /// Applies the query, returning the CSV with the selected columns and rows.
+    pub fn query(&self, query: &Query) -> Result<Csv> {
+        let query = query.resolve(&self.header)?;
+
+        let mut rows = self
+            .rows
+            .iter()
+            .filter(|row| query.matches(row))
+            .collect::<Vec<&Row>>();
+        rows.sort_by(|a, b| query.compare(a, b));
+
+        Ok(Csv {
+            header: query.project(&self.header),
+            rows: rows
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit.unwrap_or(usize::MAX))
+                .map(|row| query.project(row))
+                .collect(),
+        })
+    }
+}
+
+/// Query with the column names resolved to their indices.
+pub(super) struct ResolvedQuery {
+    columns: Vec<usize>,
+    filter: Option<ResolvedCondition>,
+    order_by: Vec<(usize, bool)>,
+    pub(super) offset: usize,
+    pub(super) limit: Option<usize>,
+}
+
+impl ResolvedQuery {
+    pub(super) fn matches(&self, row: &Row) -> bool {
+        self.filter.as_ref().is_none_or(|f| f.matches(row))
+    }
+
+    pub(super) fn project(&self, row: &Row) -> Row {
+        Row {
+            cells: self
+                .columns
+                .iter()
+                .map(|i| cell(row, *i).to_string())
+                .collect(),
+        }
+    }
+
+    fn compare(&self, a: &Row, b: &Row) -> Ordering {
+        self.order_by
+            .iter()
+            .map(|(index, descending)| {
+                let ordering = compare_cells(cell(a, *index), cell(b, *index));
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+fn column_index(header: &Row, column: &str) -> Result<usize> {
+    let cells = &header.cells;
+    cells
+        .iter()
+        .position(|c| c.trim() == column)
+        .or_else(|| {
+            cells
+                .iter()
+                .position(|c| c.trim().eq_ignore_ascii_case(column))
+        })
+        .ok_or(Error::InvalidArgument(format!(
+            "The unknown csv column provided: {column}.\nFollowing are available: [{}]",
+            cells
+                .iter()
+                .map(|c| c.trim())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        )))
+}
+
+fn resolve_condition(header: &Row, condition: &Condition) -> Result<ResolvedCondition> {
+    let resolved = match condition {
+        Condition::Comparison {
+            column,
+            operator,
+            value,
+        } => ResolvedCondition::Comparison {
+            index: column_index(header, column)?,
+            operator: *operator,
+            value: value.clone(),
+        },
+        Condition::And(left, right) => ResolvedCondition::And(
+            Box::new(resolve_condition(header, left)?),
+            Box::new(resolve_condition(header, right)?),
+        ),
+        Condition::Or(left, right) => ResolvedCondition::Or(
+            Box::new(resolve_condition(header, left)?),
+            Box::new(resolve_condition(header, right)?),
+        ),
+        Condition::Not(inner) => {
+            ResolvedCondition::Not(Box::new(resolve_condition(header, inner)?))
+        }
+    };
+
+    Ok(resolved)
+}
+
+/// Condition with the column names resolved to their indices.
+enum ResolvedCondition {
+    Comparison {
+        index: usize,
+        operator: Operator,
+        value: Literal,
+    },
+    And(Box<ResolvedCondition>, Box<ResolvedCondition>),
+    Or(Box<ResolvedCondition>, Box<ResolvedCondition>),
+    Not(Box<ResolvedCondition>),
+}
+
+impl ResolvedCondition {
+    fn matches(&self, row: &Row) -> bool {
+        match self {
+            Self::Comparison {
+                index,
+                operator,
+                value,
+            } => {
+                let cell = cell(row, *index);
+                let ordering = match value {
+                    Literal::Number(number) => {
+                        cell.parse::<f64>().ok().and_then(|c| c.partial_cmp(number))
+                    }
+                    Literal::Bool(boolean) => parse_bool(cell).map(|c| c.cmp(boolean)),
+                    Literal::Text(text) => Some(cell.cmp(text.as_str())),
+                };
+                ordering.is_some_and(|ordering| operator.accepts(ordering))
+            }
+            Self::And(left, right) => left.matches(row) && right.matches(row),
+            Self::Or(left, right) => left.matches(row) || right.matches(row),
+            Self::Not(inner) => !inner.matches(row),
+        }
+    }
+}
+
+impl Operator {
+    fn accepts(&self, ordering: Ordering) -> bool {
+        match self {
+            Self::Equal => ordering.is_eq(),
+            Self::NotEqual => ordering.is_ne(),
+            Self::Less => ordering.is_lt(),
+            Self::LessOrEqual => ordering.is_le(),
+            Self::Greater => ordering.is_gt(),
+            Self::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+}
+
+fn cell(row: &Row, index: usize) -> &str {
+    row.cells.get(index).map_or("", |c| c.trim())
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_ascii_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Numbers are ordered numerically and before the text values.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 12] = [
+    "<=", ">=", "!=", "<>", "==", "=", "<", ">", ",", "(", ")", "*",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            tokens.push(Token::Quoted(read_quoted(&mut chars, c)?));
+        } else if let Some(symbol) = symbol_at(&chars) {
+            symbol.chars().for_each(|_| {
+                chars.next();
+            });
+            tokens.push(Token::Symbol(symbol));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' || c == '\'' || symbol_at(&chars).is_some() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn read_quoted(chars: &mut Peekable<Chars>, quote: char) -> Result<String> {
+    let mut text = String::new();
+    for c in chars.by_ref() {
+        if c == quote {
+            return Ok(text);
+        }
+        text.push(c);
+    }
+    Err(Error::InvalidArgument(format!(
+        "Unterminated quoted text in the csv query: {quote}{text}"
+    )))
+}
+
+fn symbol_at(chars: &Peekable<Chars>) -> Option<&'static str> {
+    let next = chars.clone().take(2).collect::<String>();
+    SYMBOLS.into_iter().find(|s| next.starts_with(s))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            position: 0,
+        }
+    }
+
+    fn parse_query(mut self) -> Result<Query> {
+        let mut query = Query {
+            columns: None,
+            filter: None,
+            order_by: Vec::new(),
+            limit: None,
+            offset: 0,
+        };
+
+        if self.keyword("select") {
+            query.columns = self.parse_columns()?;
+        }
+        if self.keyword("where") {
+            query.filter = Some(self.parse_or()?);
+        }
+        if self.keyword("order") {
+            self.expect_keyword("by")?;
+            query.order_by = self.parse_sort_keys()?;
+        }
+        if self.keyword("limit") {
+            query.limit = Some(self.parse_count("limit")?);
+        }
+        if self.keyword("offset") {
+            query.offset = self.parse_count("offset")?;
+        }
+        if let Some(token) = self.peek() {
+            return Err(self.error(&format!("unexpected {}", describe(token))));
+        }
+
+        Ok(query)
+    }
+
+    fn parse_columns(&mut self) -> Result<Option<Vec<String>>> {
+        if self.symbol("*") {
+            return Ok(None);
+        }
+        let mut columns = vec![self.parse_column()?];
+        while self.symbol(",") {
+            columns.push(self.parse_column()?);
+        }
+        Ok(Some(columns))
+    }
+
+    fn parse_sort_keys(&mut self) -> Result<Vec<SortKey>> {
+        let mut keys = Vec::new();
+        loop {
+            let column = self.parse_column()?;
+            let descending = if self.keyword("desc") {
+                true
+            } else {
+                self.keyword("asc");
+                false
+            };
+            keys.push(SortKey { column, descending });
+            if !self.symbol(",") {
+                return Ok(keys);
+            }
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition> {
+        let mut condition = self.parse_and()?;
+        while self.keyword("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition> {
+        let mut condition = self.parse_unary()?;
+        while self.keyword("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition> {
+        if self.keyword("not") {
+            return Ok(Condition::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.symbol("(") {
+            let condition = self.parse_or()?;
+            if !self.symbol(")") {
+                return Err(self.error("expected )"));
+            }
+            return Ok(condition);
+        }
+
+        let column = self.parse_column()?;
+        let operator = match self.next() {
+            Some(Token::Symbol("=" | "==")) => Operator::Equal,
+            Some(Token::Symbol("!=" | "<>")) => Operator::NotEqual,
+            Some(Token::Symbol("<")) => Operator::Less,
+            Some(Token::Symbol("<=")) => Operator::LessOrEqual,
+            Some(Token::Symbol(">")) => Operator::Greater,
+            Some(Token::Symbol(">=")) => Operator::GreaterOrEqual,
+            _ => return Err(self.error(&format!("expected comparison after {column}"))),
+        };
+        let value = match self.next() {
+            Some(Token::Quoted(text)) => Literal::Text(text),
+            Some(Token::Word(word)) => match (word.parse::<f64>(), parse_bool(&word)) {
+                (Ok(number), _) => Literal::Number(number),
+                (_, Some(boolean)) => Literal::Bool(boolean),
+                _ => Literal::Text(word),
+            },
+            _ => return Err(self.error(&format!("expected value to compare {column} with"))),
+        };
+
+        Ok(Condition::Comparison {
+            column,
+            operator,
+            value,
+        })
+    }
+
+    fn parse_column(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Word(word) | Token::Quoted(word)) => Ok(word),
+            _ => Err(self.error("expected column name")),
+        }
+    }
+
+    fn parse_count(&mut self, clause: &str) -> Result<usize> {
+        match self.next() {
+            Some(Token::Word(word)) => word
+                .parse::<usize>()
+                .map_err(|_| self.error(&format!("{clause} requires a non-negative number"))),
+            _ => Err(self.error(&format!("{clause} requires a non-negative number"))),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {keyword}")))
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::InvalidArgument(format!(
+            "Invalid csv query at token {}: {message}",
+            self.position.min(self.tokens.len()) + 1
+        ))
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => word.clone(),
+        Token::Quoted(text) => format!("\"{text}\""),
+        Token::Symbol(symbol) => symbol.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> Csv {
+        Csv::from_file("examples/data.csv").unwrap()
+    }
+
+    fn column(csv: &Csv, index: usize) -> Vec<&str> {
+        csv.rows.iter().map(|r| r.cells[index].as_str()).collect()
+    }
+
+    #[test]
+    fn selecting_columns() {
+        let result = data()
+            .query(&Query::parse("select Occupation, name").unwrap())
+            .unwrap();
+        assert_eq!(result.header.cells, vec!["Occupation", "Name"]);
+        assert_eq!(column(&result, 1), vec!["John", "Alice", "Bob", "Eva"]);
+    }
+
+    #[test]
+    fn filtering_numbers() {
+        let result = data()
+            .query(&Query::parse("where Age > 28").unwrap())
+            .unwrap();
+        assert_eq!(column(&result, 0), vec!["John", "Bob"]);
+
+        let result = data()
+            .query(&Query::parse("where Age >= 28 and Age < 35").unwrap())
+            .unwrap();
+        assert_eq!(column(&result, 0), vec!["John", "Eva"]);
+    }
+
+    #[test]
+    fn filtering_text() {
+        let result = data()
+            .query(&Query::parse(r#"where Location = "Chicago" or Name = 'Eva'"#).unwrap())
+            .unwrap();
+        assert_eq!(column(&result, 0), vec!["Bob", "Eva"]);
+
+        let result = data()
+            .query(&Query::parse(r#"where not (Location != "Chicago")"#).unwrap())
+            .unwrap();
+        assert_eq!(column(&result, 0), vec!["Bob"]);
+    }
+
+    #[test]
+    fn sorting_by_multiple_columns() {
+        let csv = Csv::from_str("Team,Score,Name\nb,2,x\na,2,y\na,10,z\nb,1,w").unwrap();
+        let result = csv
+            .query(&Query::parse("order by Score desc, Team").unwrap())
+            .unwrap();
+        assert_eq!(column(&result, 2), vec!["z", "y", "x", "w"]);
+    }
+
+    #[test]
+    fn limiting_with_offset() {
+        let result = data()
+            .query(&Query::parse("select Name order by Age limit 2 offset 1").unwrap())
+            .unwrap();
+        assert_eq!(column(&result, 0), vec!["Eva", "John"]);
+    }
+
+    #[test]
+    fn unknown_column() {
+        let err = data()
+            .query(&Query::parse("where Salary > 10").unwrap())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Salary"));
+    }
+
+    #[test]
+    fn invalid_queries() {
+        assert!(Query::parse("select").is_err());
+        assert!(Query::parse("where Age >").is_err());
+        assert!(Query::parse("where Age 30").is_err());
+        assert!(Query::parse("order Age").is_err());
+        assert!(Query::parse("limit -1").is_err());
+        assert!(Query::parse(r#"where Name = "Bob"#).is_err());
+        assert!(Query::parse("limit 1 select Name").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::io::{Read, Write};
+
+use csv::StringRecord;
+
+use crate::error::Result;
+
+use super::{export::RowWriter, CsvDialect, CsvOptions, Row};
+
+/// Reads the CSV records one by one and writes them into the output as soon as
+/// they pass the query. Apart from the table window no rows are kept in memory.
+///
+/// The query must not sort the rows, see [`Query::is_streamable`](super::Query).
+pub(super) fn stream(
+    reader: impl Read,
+    output: &mut dyn Write,
+    options: &CsvOptions,
+) -> Result<()> {
+    let mut reader = options.dialect.reader(reader)?;
+    let header = CsvDialect::header(&mut reader)?;
+    let query = options
+        .query
+        .as_ref()
+        .map(|q| q.resolve(&header))
+        .transpose()?;
+
+    let (header, mut skip, mut remaining) = match &query {
+        Some(query) => (
+            query.project(&header),
+            query.offset,
+            query.limit.unwrap_or(usize::MAX),
+        ),
+        None => (header, 0, usize::MAX),
+    };
+    let mut writer = RowWriter::new(
+        options.format,
+        options.table.clone(),
+        header,
+        Some(options.window),
+        output,
+    );
+
+    let mut record = StringRecord::new();
+    while remaining > 0 && reader.read_record(&mut record)? {
+        let row = Row::from_record(&record);
+        let row = match &query {
+            Some(query) if !query.matches(&row) => continue,
+            Some(query) => query.project(&row),
+            None => row,
+        };
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        writer.write_row(row)?;
+        remaining -= 1;
+    }
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use crate::transmute::csv::{Csv, CsvFormat, Query};
+
+    use super::*;
+
+    fn streamed(input: &str, options: &CsvOptions) -> String {
+        let mut output = Vec::new();
+        stream(input.as_bytes(), &mut output, options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn streaming_matches_buffered_export() {
+        let input = std::fs::read_to_string("examples/data.csv").unwrap();
+        let csv = Csv::from_str(&input).unwrap();
+        for format in [
+            CsvFormat::Json,
+            CsvFormat::JsonLines,
+            CsvFormat::Markdown,
+            CsvFormat::Html,
+            CsvFormat::Tsv,
+            CsvFormat::Table,
+        ] {
+            let options = CsvOptions {
+                format,
+                ..Default::default()
+            };
+            assert_eq!(streamed(&input, &options), csv.export(format).unwrap());
+        }
+    }
+
+    #[test]
+    fn streaming_with_query() {
+        let options = CsvOptions {
+            format: CsvFormat::Tsv,
+            query: Some(Query::parse("select Name where Age > 26 limit 2 offset 1").unwrap()),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        stream(
+            File::open("examples/data.csv").unwrap(),
+            &mut output,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "Name\nBob\nEva");
+    }
+
+    #[test]
+    fn streaming_table_in_windows() {
+        let options = CsvOptions {
+            window: 3,
+            ..Default::default()
+        };
+        let output = streamed("n\n1\n2\n3\n4\n5\n6\n7", &options);
+        // the numeric column is aligned to the right
+        assert_eq!(output.matches("|  n |").count(), 3);
+    }
+
+    #[test]
+    fn streaming_invalid_content() {
+        let mut output = Vec::new();
+        let result = stream(
+            File::open("examples/invalid_data.csv").unwrap(),
+            &mut output,
+            &CsvOptions::default(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,441 @@
+use std::env;
+
+use prettytable::{
+    format::{self, FormatBuilder, LinePosition, LineSeparator, TableFormat},
+    Cell, Row as PrettyRow, Table,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::{
+    describe::{is_null, ColumnType},
+    Csv, Row,
+};
+use crate::transmute::reflow::break_lines;
+
+/// Columns are not narrowed below this width to fit the table into the terminal.
+const MIN_FIT_WIDTH: usize = 5;
+
+/// Borders of the table rendering the CSV.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TableStyle {
+    Ascii,
+    /// Box-drawing characters.
+    Unicode,
+    /// GitHub flavored Markdown table, the `|` within the cells is escaped.
+    Markdown,
+    /// Columns separated by spaces only.
+    None,
+}
+
+impl TableStyle {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Option<Self> {
+        match text {
+            "ascii" => Some(Self::Ascii),
+            "unicode" => Some(Self::Unicode),
+            "markdown" => Some(Self::Markdown),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn all_variants() -> Vec<&'static str> {
+        vec!["ascii", "unicode", "markdown", "none"]
+    }
+
+    fn format(&self) -> TableFormat {
+        match self {
+            Self::Ascii => *format::consts::FORMAT_NO_TITLE,
+            Self::Unicode => *format::consts::FORMAT_BOX_CHARS,
+            Self::Markdown => FormatBuilder::new()
+                .column_separator('|')
+                .borders('|')
+                .separator(LinePosition::Title, LineSeparator::new('-', '|', '|', '|'))
+                .padding(1, 1)
+                .build(),
+            Self::None => *format::consts::FORMAT_CLEAN,
+        }
+    }
+
+    /// Width of the padding and the separators of the columns.
+    fn overhead(&self, columns: usize) -> usize {
+        match self {
+            Self::None => 2 * columns,
+            _ => 3 * columns + 1,
+        }
+    }
+}
+
+/// Alignment of a column, including its header.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnAlignment {
+    /// Right for the columns holding only numbers, left for the other ones.
+    Auto,
+    Left,
+    Right,
+    Center,
+}
+
+impl ColumnAlignment {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Option<Self> {
+        match text {
+            "auto" => Some(Self::Auto),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "center" => Some(Self::Center),
+            _ => None,
+        }
+    }
+
+    pub fn all_variants() -> Vec<&'static str> {
+        vec!["auto", "left", "right", "center"]
+    }
+}
+
+/// What happens to the cells wider than their column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overflow {
+    /// The end of the cell is replaced by `…`.
+    Ellipsis,
+    /// The cell is broken into lines between the words. Markdown tables cannot
+    /// hold several lines in a cell, so they use the ellipsis instead.
+    Wrap,
+}
+
+impl Overflow {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Option<Self> {
+        match text {
+            "ellipsis" => Some(Self::Ellipsis),
+            "wrap" => Some(Self::Wrap),
+            _ => None,
+        }
+    }
+
+    pub fn all_variants() -> Vec<&'static str> {
+        vec!["ellipsis", "wrap"]
+    }
+}
+
+/// How the `table` format of the csv transmutation is rendered. The widths are
+/// display widths, so wide characters such as CJK and emoji count twice.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableOptions {
+    pub style: TableStyle,
+    /// Alignments of the columns by position, the columns without one are
+    /// aligned automatically.
+    pub alignments: Vec<ColumnAlignment>,
+    /// Maximum width of the cells.
+    pub max_width: Option<usize>,
+    pub overflow: Overflow,
+    /// Narrows the widest columns until the table fits into the width of the
+    /// terminal, or of `COLUMNS` when the output is not a terminal.
+    pub fit_terminal: bool,
+    /// Adds the decorative row of crabs at the bottom.
+    pub crab_row: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            style: TableStyle::Ascii,
+            alignments: Vec::new(),
+            max_width: None,
+            overflow: Overflow::Ellipsis,
+            fit_terminal: false,
+            crab_row: true,
+        }
+    }
+}
+
+impl TableOptions {
+    fn overflow(&self) -> Overflow {
+        match self.style {
+            TableStyle::Markdown => Overflow::Ellipsis,
+            _ => self.overflow,
+        }
+    }
+}
+
+impl Csv {
+    /// Renders the CSV as a table with the trimmed cells.
+    pub fn table(&self, options: &TableOptions) -> String {
+        let rows = std::iter::once(&self.header)
+            .chain(&self.rows)
+            .map(|row| cells(row, options.style))
+            .collect::<Vec<Vec<String>>>();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut widths = (0..columns)
+            .map(|index| {
+                let width = rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .flat_map(|cell| cell.lines())
+                    .map(UnicodeWidthStr::width)
+                    .max()
+                    .unwrap_or(0);
+                options.max_width.map_or(width, |max| width.min(max.max(1)))
+            })
+            .collect::<Vec<usize>>();
+        if options.fit_terminal {
+            if let Some(terminal) = terminal_width() {
+                fit(
+                    &mut widths,
+                    terminal.saturating_sub(options.style.overhead(columns)),
+                );
+            }
+        }
+        let alignments = (0..columns)
+            .map(|index| {
+                match options
+                    .alignments
+                    .get(index)
+                    .unwrap_or(&ColumnAlignment::Auto)
+                {
+                    ColumnAlignment::Auto if self.is_numeric(index) => format::Alignment::RIGHT,
+                    ColumnAlignment::Auto | ColumnAlignment::Left => format::Alignment::LEFT,
+                    ColumnAlignment::Right => format::Alignment::RIGHT,
+                    ColumnAlignment::Center => format::Alignment::CENTER,
+                }
+            })
+            .collect::<Vec<format::Alignment>>();
+
+        let pretty_row = |row: &[String]| {
+            PrettyRow::new(
+                row.iter()
+                    .enumerate()
+                    .map(|(index, cell)| {
+                        let cell = fit_cell(cell, widths[index], options.overflow());
+                        Cell::new_align(&cell, alignments[index])
+                    })
+                    .collect(),
+            )
+        };
+        let mut table = Table::new();
+        table.set_format(options.style.format());
+        let mut rows = rows.iter();
+        if let Some(header) = rows.next() {
+            table.set_titles(pretty_row(header));
+        }
+        rows.for_each(|row| {
+            table.add_row(pretty_row(row));
+        });
+
+        // some fun
+        if options.crab_row {
+            table.add_row(PrettyRow::new(
+                (0..columns)
+                    .map(|_| Cell::new_align("🦀", format::Alignment::CENTER))
+                    .collect(),
+            ));
+        }
+
+        table.to_string()
+    }
+
+    /// Whether all the values of the column are numbers, nulls aside.
+    fn is_numeric(&self, index: usize) -> bool {
+        let values = self
+            .rows
+            .iter()
+            .filter_map(|row| row.cells.get(index))
+            .map(|cell| cell.trim())
+            .filter(|value| !is_null(value));
+        matches!(
+            ColumnType::infer(values),
+            ColumnType::Integer | ColumnType::Float
+        )
+    }
+}
+
+fn cells(row: &Row, style: TableStyle) -> Vec<String> {
+    row.cells
+        .iter()
+        .map(|cell| match style {
+            TableStyle::Markdown => cell.trim().replace('|', "\\|").replace('\n', " "),
+            _ => cell.trim().to_string(),
+        })
+        .collect()
+}
+
+fn terminal_width() -> Option<usize> {
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(width), _)) => Some(width as usize),
+        None => env::var("COLUMNS").ok()?.parse().ok(),
+    }
+}
+
+/// Narrows the widest columns one by one until the columns fit into the width.
+fn fit(widths: &mut [usize], available: usize) {
+    while widths.iter().sum::<usize>() > available {
+        match widths.iter_mut().max() {
+            Some(widest) if *widest > MIN_FIT_WIDTH => *widest -= 1,
+            _ => break,
+        }
+    }
+}
+
+fn fit_cell(cell: &str, width: usize, overflow: Overflow) -> String {
+    cell.lines()
+        .flat_map(|line| match overflow {
+            _ if line.width() <= width => vec![line.to_string()],
+            Overflow::Ellipsis => {
+                let (head, _) = split_at_width(line, width.saturating_sub(1));
+                vec![format!("{head}…")]
+            }
+            Overflow::Wrap => wrap(line, width),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Breaks the text into lines between the words, words wider than the width
+/// are broken too.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let pieces = text.split_whitespace().flat_map(|word| pieces(word, width));
+    break_lines(pieces, width)
+        .iter()
+        .map(|words| words.join(" "))
+        .collect()
+}
+
+/// Splits the word wider than the width into pieces fitting into it, a
+/// character wider than the width gets a piece of its own.
+fn pieces(mut word: &str, width: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    while !word.is_empty() {
+        let (head, tail) = match split_at_width(word, width) {
+            ("", _) => word.split_at(word.chars().next().map_or(0, char::len_utf8)),
+            split => split,
+        };
+        pieces.push(head);
+        word = tail;
+    }
+    pieces
+}
+
+/// Splits the text after the characters fitting into the width.
+fn split_at_width(text: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return text.split_at(index);
+        }
+    }
+    (text, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Name,Age,Note\nFerris,7,likes | pipes\nCorro,12.5,";
+
+    fn table(options: TableOptions) -> String {
+        Csv::from_str(INPUT).unwrap().table(&options)
+    }
+
+    #[test]
+    fn rendering_styles() {
+        let options = TableOptions {
+            crab_row: false,
+            ..TableOptions::default()
+        };
+        assert_eq!(
+            table(options.clone()),
+            "\
++--------+------+---------------+
+| Name   |  Age | Note          |
++--------+------+---------------+
+| Ferris |    7 | likes | pipes |
++--------+------+---------------+
+| Corro  | 12.5 |               |
++--------+------+---------------+
+"
+        );
+        let markdown = table(TableOptions {
+            style: TableStyle::Markdown,
+            ..options.clone()
+        });
+        assert_eq!(
+            markdown,
+            "\
+| Name   |  Age | Note           |
+|--------|------|----------------|
+| Ferris |    7 | likes \\| pipes |
+| Corro  | 12.5 |                |
+"
+        );
+        let none = table(TableOptions {
+            style: TableStyle::None,
+            ..options
+        });
+        assert_eq!(
+            none,
+            " Name     Age  Note \n Ferris     7  likes | pipes \n Corro   12.5   \n"
+        );
+    }
+
+    #[test]
+    fn aligning_columns() {
+        let unicode = table(TableOptions {
+            style: TableStyle::Unicode,
+            alignments: vec![ColumnAlignment::Right, ColumnAlignment::Left],
+            ..TableOptions::default()
+        });
+        assert!(
+            unicode.contains("│   Name │ Age  │ Note          │"),
+            "{unicode}"
+        );
+        assert!(
+            unicode.contains("│ Ferris │ 7    │ likes | pipes │"),
+            "{unicode}"
+        );
+        assert!(
+            unicode.contains("│   🦀   │  🦀  │      🦀       │"),
+            "{unicode}"
+        );
+    }
+
+    #[test]
+    fn limiting_width() {
+        let options = TableOptions {
+            style: TableStyle::None,
+            max_width: Some(5),
+            crab_row: false,
+            ..TableOptions::default()
+        };
+        assert_eq!(
+            table(options.clone()),
+            " Name    Age  Note \n Ferr…     7  like… \n Corro  12.5   \n"
+        );
+        let wrapped = table(TableOptions {
+            overflow: Overflow::Wrap,
+            ..options
+        });
+        assert_eq!(
+            wrapped,
+            " Name    Age  Note \n Ferri     7  likes \n s            | \n              pipes \n Corro  12.5   \n"
+        );
+    }
+
+    #[test]
+    fn wrapping_words() {
+        assert_eq!(wrap("a bb ccccc", 4), vec!["a bb", "cccc", "c"]);
+        assert_eq!(wrap("日本語", 3), vec!["日", "本", "語"]);
+        assert_eq!(wrap("🦀", 1), vec!["🦀"]);
+        assert_eq!(wrap("", 3), vec![""]);
+    }
+
+    #[test]
+    fn fitting_columns() {
+        let mut widths = vec![20, 8, 3];
+        fit(&mut widths, 25);
+        assert_eq!(widths, vec![14, 8, 3]);
+        fit(&mut widths, 0);
+        assert_eq!(widths, vec![5, 5, 3]);
+    }
+}
@@ -0,0 +1,291 @@
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    error::{Error, Result},
+    transmute::csv::{Csv, CsvFormat},
+};
+
+/// Structured data formats converted into each other through the JSON data
+/// model, so the order of the keys is kept.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    /// TOML documents are tables, so only JSON objects can be written as TOML
+    /// and their values cannot be `null`. Dates and times are read as strings.
+    Toml,
+}
+
+impl DataFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Yaml => "YAML",
+            Self::Toml => "TOML",
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Result<Value> {
+        match self {
+            Self::Json => Ok(serde_json::from_str(text)?),
+            Self::Yaml => serde_yaml::from_str(text).map_err(|err| match err.location() {
+                Some(location) => self.error(
+                    &err.to_string(),
+                    Some(location.line()),
+                    Some(location.column()),
+                ),
+                None => self.error(&err.to_string(), None, None),
+            }),
+            Self::Toml => {
+                let table = text.parse::<toml::Table>().map_err(|err| {
+                    let (line, column) = match err.span() {
+                        Some(span) => line_and_column(text, span.start),
+                        None => (None, None),
+                    };
+                    self.error(err.message(), line, column)
+                })?;
+                toml_to_json(toml::Value::Table(table))
+            }
+        }
+    }
+
+    /// Renders the value indented, the way the format is usually written by hand.
+    pub fn render(&self, value: &Value) -> Result<String> {
+        match self {
+            Self::Json => Ok(serde_json::to_string_pretty(value)?),
+            Self::Yaml => serde_yaml::to_string(value)
+                .map_err(|err| Error::InvalidArgument(format!("Cannot write YAML: {err}"))),
+            Self::Toml => match json_to_toml(value, "")? {
+                toml::Value::Table(table) => toml::to_string_pretty(&table)
+                    .map_err(|err| Error::InvalidArgument(format!("Cannot write TOML: {err}"))),
+                _ => Err(Error::InvalidArgument(
+                    "Only an object can be written as a TOML document".to_string(),
+                )),
+            },
+        }
+    }
+
+    /// Parses the text and renders the value in the other format.
+    pub fn convert(&self, text: &str, to: DataFormat) -> Result<String> {
+        to.render(&self.parse(text)?)
+    }
+
+    /// The parse error without the position the parsers append to their messages.
+    pub(crate) fn error(&self, message: &str, line: Option<usize>, column: Option<usize>) -> Error {
+        let message = match (line, column) {
+            (Some(line), Some(column)) => {
+                message.replace(&format!(" at line {line} column {column}"), "")
+            }
+            _ => message.to_string(),
+        };
+        Error::Parse {
+            format: self.name().to_string(),
+            line,
+            column,
+            message: message.trim_end().to_string(),
+        }
+    }
+}
+
+/// Removes all the whitespace between the JSON tokens.
+pub fn minify_json(text: &str) -> Result<String> {
+    let value = DataFormat::Json.parse(text)?;
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// Converts the CSV into a JSON array with an object per row, keyed by the header.
+pub fn csv_to_json(text: &str) -> Result<String> {
+    Csv::from_str(text)?.export(CsvFormat::Json)
+}
+
+/// Converts a JSON array of objects, or a single object, into CSV. The header
+/// holds the keys in the order they first appear, so the objects may miss some
+/// of them. Nested arrays and objects are written as JSON.
+pub fn json_to_csv(text: &str) -> Result<String> {
+    let value = DataFormat::Json.parse(text)?;
+    let objects = match &value {
+        Value::Array(items) => items.iter().collect::<Vec<&Value>>(),
+        object => vec![object],
+    };
+    let objects = objects
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| match item {
+            Value::Object(object) => Ok(object),
+            _ => Err(Error::InvalidArgument(format!(
+                "Converting JSON to CSV requires objects, provided: {item} at index {index}"
+            ))),
+        })
+        .collect::<Result<Vec<&Map<String, Value>>>>()?;
+
+    let mut header: Vec<&String> = Vec::new();
+    for key in objects.iter().flat_map(|object| object.keys()) {
+        if !header.contains(&key) {
+            header.push(key);
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&header)?;
+    for object in objects {
+        writer.write_record(header.iter().map(|key| match object.get(*key) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(text)) => text.clone(),
+            Some(value) => value.to_string(),
+        }))?;
+    }
+    let output = writer
+        .into_inner()
+        .map_err(|err| Error::Io(err.into_error()))?;
+
+    Ok(String::from_utf8(output)?
+        .trim_end_matches('\n')
+        .to_string())
+}
+
+fn toml_to_json(value: toml::Value) -> Result<Value> {
+    Ok(match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(number) => Value::from(number),
+        toml::Value::Float(number) => {
+            Number::from_f64(number).map(Value::Number).ok_or_else(|| {
+                Error::InvalidArgument(format!("The TOML float {number} has no JSON equivalent"))
+            })?
+        }
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(toml_to_json)
+                .collect::<Result<Vec<Value>>>()?,
+        ),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| Ok((key, toml_to_json(value)?)))
+                .collect::<Result<Map<String, Value>>>()?,
+        ),
+    })
+}
+
+/// The path is the dotted key of the value, reported when it is `null`.
+fn json_to_toml(value: &Value, path: &str) -> Result<toml::Value> {
+    Ok(match value {
+        Value::Null => {
+            return Err(Error::InvalidArgument(format!(
+                "TOML has no null value, found at {}",
+                if path.is_empty() { "the root" } else { path }
+            )))
+        }
+        Value::Bool(boolean) => toml::Value::Boolean(*boolean),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => toml::Value::Integer(integer),
+            None => toml::Value::Float(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(text) => toml::Value::String(text.clone()),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| json_to_toml(item, &format!("{path}[{index}]")))
+                .collect::<Result<Vec<toml::Value>>>()?,
+        ),
+        Value::Object(object) => toml::Value::Table(
+            object
+                .iter()
+                .map(|(key, value)| {
+                    let path = match path {
+                        "" => key.clone(),
+                        path => format!("{path}.{key}"),
+                    };
+                    Ok((key.clone(), json_to_toml(value, &path)?))
+                })
+                .collect::<Result<toml::Table>>()?,
+        ),
+    })
+}
+
+/// Line and column of the byte offset, both counted from 1.
+fn line_and_column(text: &str, offset: usize) -> (Option<usize>, Option<usize>) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        Some(before.matches('\n').count() + 1),
+        Some(before[line_start..].chars().count() + 1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{"name":"Ferris","legs":10,"tags":["crab","rust"],"home":{"sea":true}}"#;
+
+    #[test]
+    fn converting_between_formats() {
+        let yaml = DataFormat::Json.convert(JSON, DataFormat::Yaml).unwrap();
+        assert_eq!(
+            yaml,
+            "name: Ferris\nlegs: 10\ntags:\n- crab\n- rust\nhome:\n  sea: true\n"
+        );
+        let toml = DataFormat::Yaml.convert(&yaml, DataFormat::Toml).unwrap();
+        assert_eq!(
+            toml,
+            "name = \"Ferris\"\nlegs = 10\ntags = [\n    \"crab\",\n    \"rust\",\n]\n\n[home]\nsea = true\n"
+        );
+        let json = DataFormat::Toml.convert(&toml, DataFormat::Json).unwrap();
+        assert_eq!(minify_json(&json).unwrap(), JSON);
+    }
+
+    #[test]
+    fn reporting_parse_position() {
+        let err = DataFormat::Json
+            .parse("{\n  \"a\": 1,\n  \"b\" 2\n}")
+            .unwrap_err();
+        assert_eq!(err.exit_code(), 8);
+        assert_eq!(
+            err.to_string(),
+            "Invalid JSON at line 3, column 7: expected `:`"
+        );
+
+        let err = DataFormat::Yaml.parse("a: 1\nb: [2\n").unwrap_err();
+        assert!(matches!(err, Error::Parse { line: Some(3), .. }), "{err}");
+
+        let err = DataFormat::Toml.parse("a = 1\nb = = 2\n").unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::Parse {
+                    line: Some(2),
+                    column: Some(5),
+                    ..
+                }
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn rejecting_values_without_toml_equivalent() {
+        assert!(DataFormat::Json.convert("[1]", DataFormat::Toml).is_err());
+        let err = DataFormat::Json
+            .convert(r#"{"a":{"b":[1,null]}}"#, DataFormat::Toml)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "TOML has no null value, found at a.b[1]");
+    }
+
+    #[test]
+    fn converting_csv() {
+        let json = csv_to_json("name,legs\nFerris,10\nCorro,\"4,5\"").unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[1]["legs"], "4,5");
+
+        let csv = json_to_csv(r#"[{"name":"Ferris","legs":10},{"tags":["a"],"name":null}]"#);
+        assert_eq!(
+            csv.unwrap(),
+            "name,legs,tags\nFerris,10,\n,,\"[\"\"a\"\"]\""
+        );
+        assert!(json_to_csv("[1, 2]").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use data_encoding::{
+    DecodeError, Specification, BASE32, BASE64, BASE64URL_NOPAD, HEXLOWER_PERMISSIVE,
+};
+
+use crate::error::{Error, Result};
+
+/// Binary-to-text and escaping encodings of the text.
+///
+/// The decoders skip the whitespace of wrapped base64, base32 and hex input,
+/// invalid input is reported with the byte offset of the offending character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    /// Standard base64 with padding.
+    Base64,
+    /// URL and filename safe base64 without padding, the padding is accepted
+    /// when decoding.
+    Base64Url,
+    Base32,
+    /// Lowercase hex, either case is accepted when decoding.
+    Hex,
+    /// Percent-encoding of everything but the unreserved characters of RFC 3986.
+    Percent,
+    /// Escaping of the characters with a special meaning in HTML.
+    Html,
+}
+
+/// Named HTML entities decoded besides the numeric ones.
+const HTML_ENTITIES: [(&str, char); 16] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+];
+
+impl Encoding {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Base64 => "base64",
+            Self::Base64Url => "base64url",
+            Self::Base32 => "base32",
+            Self::Hex => "hex",
+            Self::Percent => "percent-encoded",
+            Self::Html => "HTML",
+        }
+    }
+
+    pub fn encode(&self, text: &str) -> String {
+        let bytes = text.as_bytes();
+        match self {
+            Self::Base64 => BASE64.encode(bytes),
+            Self::Base64Url => BASE64URL_NOPAD.encode(bytes),
+            Self::Base32 => BASE32.encode(bytes),
+            Self::Hex => HEXLOWER_PERMISSIVE.encode(bytes),
+            Self::Percent => bytes
+                .iter()
+                .map(|&b| match b {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                        char::from(b).to_string()
+                    }
+                    b => format!("%{b:02X}"),
+                })
+                .collect(),
+            Self::Html => text
+                .chars()
+                .map(|c| match c {
+                    '&' => "&amp;".to_string(),
+                    '<' => "&lt;".to_string(),
+                    '>' => "&gt;".to_string(),
+                    '"' => "&quot;".to_string(),
+                    '\'' => "&#39;".to_string(),
+                    c => c.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Decodes the text, the decoded bytes have to be valid UTF-8.
+    pub fn decode(&self, text: &str) -> Result<String> {
+        let bytes = match self {
+            Self::Base64 => self.decode_with(BASE64.specification(), text)?,
+            Self::Base64Url => self.decode_with(
+                BASE64URL_NOPAD.specification(),
+                text.trim_end_matches(['=', ' ', '\t', '\r', '\n']),
+            )?,
+            Self::Base32 => self.decode_with(BASE32.specification(), text)?,
+            Self::Hex => self.decode_with(HEXLOWER_PERMISSIVE.specification(), text)?,
+            Self::Percent => self.decode_percent(text)?,
+            Self::Html => return self.decode_html(text),
+        };
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    fn decode_with(&self, mut specification: Specification, text: &str) -> Result<Vec<u8>> {
+        specification.ignore.push_str(" \t\r\n");
+        let encoding = specification
+            .encoding()
+            .expect("the specification is valid");
+        encoding
+            .decode(text.as_bytes())
+            .map_err(|e: DecodeError| self.error(e.position, e.kind.to_string()))
+    }
+
+    fn decode_percent(&self, text: &str) -> Result<Vec<u8>> {
+        let bytes = text.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] != b'%' {
+                decoded.push(bytes[i]);
+                i += 1;
+                continue;
+            }
+            let byte = text
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| {
+                    self.error(i, "% should be followed by two hex digits".to_string())
+                })?;
+            decoded.push(byte);
+            i += 3;
+        }
+        Ok(decoded)
+    }
+
+    /// Decodes the named and numeric character references. An ampersand which
+    /// does not start a reference terminated by `;` is kept as it is.
+    fn decode_html(&self, text: &str) -> Result<String> {
+        let mut decoded = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('&') {
+            decoded.push_str(&rest[..start]);
+            let reference = &rest[start + 1..];
+            let end = reference.find(';').filter(|&end| {
+                reference[..end]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '#')
+            });
+            let Some(end) = end.filter(|&end| end > 0) else {
+                decoded.push('&');
+                rest = reference;
+                continue;
+            };
+
+            let offset = text.len() - rest.len() + start;
+            let name = &reference[..end];
+            let character = match name.strip_prefix('#') {
+                Some(number) => {
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16),
+                        None => number.parse::<u32>(),
+                    };
+                    code.ok().and_then(char::from_u32).ok_or_else(|| {
+                        self.error(
+                            offset,
+                            format!("&{name}; is not a valid character reference"),
+                        )
+                    })?
+                }
+                None => HTML_ENTITIES
+                    .iter()
+                    .find(|(entity, _)| *entity == name)
+                    .map(|(_, c)| *c)
+                    .ok_or_else(|| self.error(offset, format!("&{name}; is an unknown entity")))?,
+            };
+            decoded.push(character);
+            rest = &reference[end + 1..];
+        }
+        decoded.push_str(rest);
+        Ok(decoded)
+    }
+
+    fn error(&self, offset: usize, message: String) -> Error {
+        Error::Decode {
+            encoding: self.name().to_string(),
+            offset,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODINGS: [Encoding; 6] = [
+        Encoding::Base64,
+        Encoding::Base64Url,
+        Encoding::Base32,
+        Encoding::Hex,
+        Encoding::Percent,
+        Encoding::Html,
+    ];
+
+    #[test]
+    fn encoding() {
+        let text = "Tom & Jerry? <ü>";
+        let expected = [
+            "VG9tICYgSmVycnk/IDzDvD4=",
+            "VG9tICYgSmVycnk_IDzDvD4",
+            "KRXW2IBGEBFGK4TSPE7SAPGDXQ7A====",
+            "546f6d2026204a657272793f203cc3bc3e",
+            "Tom%20%26%20Jerry%3F%20%3C%C3%BC%3E",
+            "Tom &amp; Jerry? &lt;ü&gt;",
+        ];
+        for (encoding, expected) in ENCODINGS.iter().zip(expected) {
+            assert_eq!(encoding.encode(text), expected);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "Crabs 🦀 say \"hi\" & 'bye'\n\tnew line? 100% sure/maybe+";
+        for encoding in ENCODINGS {
+            assert_eq!(
+                encoding.decode(&encoding.encode(text)).unwrap(),
+                text,
+                "{encoding:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decoding_wrapped_and_lenient_input() {
+        assert_eq!(Encoding::Base64.decode("aGVs\nbG8=\n").unwrap(), "hello");
+        assert_eq!(Encoding::Base64Url.decode("aGVsbG8=").unwrap(), "hello");
+        assert_eq!(Encoding::Hex.decode("68 65 6C 6c 6f").unwrap(), "hello");
+        assert_eq!(Encoding::Percent.decode("a+b%2fc").unwrap(), "a+b/c");
+        assert_eq!(
+            Encoding::Html
+                .decode("AT&T &amp; &#x1F980;&#33; &copy")
+                .unwrap(),
+            "AT&T & 🦀! &copy"
+        );
+    }
+
+    #[test]
+    fn reporting_offset_of_invalid_input() {
+        let offset = |encoding: Encoding, text: &str| match encoding.decode(text) {
+            Err(Error::Decode { offset, .. }) => offset,
+            result => panic!("unexpected result: {result:?}"),
+        };
+        assert_eq!(offset(Encoding::Base64, "aGVs*G8="), 4);
+        assert_eq!(offset(Encoding::Base64, "aGVs\nb*8="), 6);
+        assert_eq!(offset(Encoding::Hex, "6865zz"), 4);
+        assert_eq!(offset(Encoding::Percent, "100%"), 3);
+        assert_eq!(offset(Encoding::Percent, "a%+1"), 1);
+        assert_eq!(offset(Encoding::Html, "a &bogus; b"), 2);
+
+        let err = Encoding::Hex.decode("ff").unwrap_err();
+        assert!(matches!(err, Error::InvalidUtf8 { offset: Some(0) }));
+    }
+}
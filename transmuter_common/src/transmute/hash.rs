@@ -0,0 +1,404 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+};
+
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{
+    error::{Error, Result},
+    transmute::{
+        arguments::{parse_leading_options, parse_options, Parameter},
+        read_text, Source, Transmute,
+    },
+};
+
+/// Hash functions of the hash transmutations, the first four can be keyed as HMAC.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+    Sha1,
+    Md5,
+    Blake3,
+    Crc32,
+}
+
+impl HashAlgorithm {
+    pub fn all_variants() -> Vec<Self> {
+        vec![
+            Self::Sha256,
+            Self::Sha512,
+            Self::Sha1,
+            Self::Md5,
+            Self::Blake3,
+            Self::Crc32,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+            Self::Sha1 => "sha1",
+            Self::Md5 => "md5",
+            Self::Blake3 => "blake3",
+            Self::Crc32 => "crc32",
+        }
+    }
+
+    /// Name of the HMAC variant, if the algorithm has one.
+    pub fn hmac_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Sha256 => Some("hmac-sha256"),
+            Self::Sha512 => Some("hmac-sha512"),
+            Self::Sha1 => Some("hmac-sha1"),
+            Self::Md5 => Some("hmac-md5"),
+            Self::Blake3 | Self::Crc32 => None,
+        }
+    }
+
+    /// Hashes everything read from the input, keyed by the HMAC key if provided.
+    pub fn hash(&self, key: Option<&[u8]>, input: &mut dyn Read) -> Result<Vec<u8>> {
+        match (self, key) {
+            (Self::Sha256, None) => digest::<Sha256>(input),
+            (Self::Sha512, None) => digest::<Sha512>(input),
+            (Self::Sha1, None) => digest::<Sha1>(input),
+            (Self::Md5, None) => digest::<Md5>(input),
+            (Self::Sha256, Some(key)) => mac(Hmac::<Sha256>::new_from_slice(key), input),
+            (Self::Sha512, Some(key)) => mac(Hmac::<Sha512>::new_from_slice(key), input),
+            (Self::Sha1, Some(key)) => mac(Hmac::<Sha1>::new_from_slice(key), input),
+            (Self::Md5, Some(key)) => mac(Hmac::<Md5>::new_from_slice(key), input),
+            (Self::Blake3, None) => {
+                let mut hasher = blake3::Hasher::new();
+                read_chunks(input, |chunk| {
+                    hasher.update(chunk);
+                })?;
+                Ok(hasher.finalize().as_bytes().to_vec())
+            }
+            (Self::Crc32, None) => {
+                let mut hasher = crc32fast::Hasher::new();
+                read_chunks(input, |chunk| hasher.update(chunk))?;
+                Ok(hasher.finalize().to_be_bytes().to_vec())
+            }
+            (Self::Blake3 | Self::Crc32, Some(_)) => Err(Error::InvalidArgument(format!(
+                "The {} hash has no HMAC variant",
+                self.name()
+            ))),
+        }
+    }
+}
+
+fn digest<D: Digest>(input: &mut dyn Read) -> Result<Vec<u8>> {
+    let mut hasher = D::new();
+    read_chunks(input, |chunk| hasher.update(chunk))?;
+    Ok(hasher.finalize().to_vec())
+}
+
+fn mac<M: Mac>(
+    mac: std::result::Result<M, hmac::digest::InvalidLength>,
+    input: &mut dyn Read,
+) -> Result<Vec<u8>> {
+    // HMAC takes keys of any length, the error is only part of the generic API
+    let mut mac = mac.map_err(|e| Error::InvalidArgument(format!("Invalid HMAC key: {e}")))?;
+    read_chunks(input, |chunk| mac.update(chunk))?;
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn read_chunks(input: &mut dyn Read, mut consume: impl FnMut(&[u8])) -> Result<()> {
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        match input.read(&mut buffer)? {
+            0 => return Ok(()),
+            read => consume(&buffer[..read]),
+        }
+    }
+}
+
+/// Options of the hash transmutations in the `--name value` or `--name=value`
+/// form, i.e. the `--key <key>` of the HMAC variants.
+#[derive(Clone, Debug, Default)]
+pub struct HashOptions {
+    pub key: Option<String>,
+}
+
+impl HashOptions {
+    /// Builds the options from CLI arguments. Every argument has to be an option.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut options = Self::default();
+        parse_options("hash", args, |name, value| options.set(name, value))?;
+        Ok(options)
+    }
+
+    /// Consumes the options at the beginning of the input, overriding the current
+    /// values. Returns the updated options and the rest of the input.
+    pub fn parse_leading(mut self, input: &str) -> Result<(Self, &str)> {
+        let rest = parse_leading_options("hash", input, |name, value| self.set(name, value))?;
+        Ok((self, rest))
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "key" => self.key = Some(value.to_string()),
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "The unsupported hash option provided: --{name}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+const KEY: Parameter = Parameter::new("key", "secret key of the HMAC");
+
+/// Transmutation printing the lowercase hex digest of the input, or of the file
+/// it is the path of. The HMAC variants, e.g. `hmac-sha256 <key>`, require the
+/// key, which can also precede the file path as `--key <key>` when reading files.
+///
+/// Text streamed into the transmutation is hashed as it is read, so neither
+/// large nor binary inputs have to be valid UTF-8 or fit into memory.
+pub struct HashTransmutation {
+    algorithm: HashAlgorithm,
+    source: Source,
+    hmac: bool,
+    options: HashOptions,
+}
+
+impl HashTransmutation {
+    pub fn new(algorithm: HashAlgorithm, source: Source) -> Self {
+        Self {
+            algorithm,
+            source,
+            hmac: false,
+            options: HashOptions::default(),
+        }
+    }
+
+    /// The HMAC variant, `None` for the algorithms without one.
+    pub fn hmac(algorithm: HashAlgorithm, source: Source) -> Option<Self> {
+        algorithm.hmac_name().map(|_| Self {
+            hmac: true,
+            ..Self::new(algorithm, source)
+        })
+    }
+
+    pub fn with_options(mut self, options: HashOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The hashes of all the algorithms followed by the HMAC variants.
+    pub fn all_variants(source: Source) -> Vec<Self> {
+        let algorithms = HashAlgorithm::all_variants();
+        let hashes = algorithms.iter().map(|a| Self::new(*a, source));
+        let hmacs = algorithms.iter().filter_map(|a| Self::hmac(*a, source));
+        hashes.chain(hmacs).collect()
+    }
+
+    fn hash(&self, options: &HashOptions, input: &mut dyn Read) -> Result<String> {
+        let key = match (&options.key, self.hmac) {
+            (Some(key), true) => Some(key.as_bytes()),
+            (None, true) => {
+                return Err(Error::InvalidArgument(format!(
+                    "The {} transmutation requires a key provided as {} or by --key <key>",
+                    self.name(),
+                    self.usage()
+                )))
+            }
+            (_, false) => None,
+        };
+        let digest = self.algorithm.hash(key, input)?;
+        Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+    }
+
+    /// Only the HMAC variants take options preceding the file path.
+    fn hash_file(&self, input: &str) -> Result<String> {
+        let (options, file_path) = match self.hmac {
+            true => self.options.clone().parse_leading(input)?,
+            false => (self.options.clone(), input),
+        };
+        let mut file = BufReader::new(File::open(file_path.trim())?);
+        self.hash(&options, &mut file)
+    }
+}
+
+impl Transmute for HashTransmutation {
+    fn name(&self) -> &str {
+        match self.hmac {
+            true => self.algorithm.hmac_name().unwrap_or_default(),
+            false => self.algorithm.name(),
+        }
+    }
+
+    fn description(&self) -> &str {
+        match (self.algorithm, self.hmac) {
+            (HashAlgorithm::Sha256, false) => "Prints the SHA-256 hash in hex",
+            (HashAlgorithm::Sha512, false) => "Prints the SHA-512 hash in hex",
+            (HashAlgorithm::Sha1, false) => "Prints the SHA-1 hash in hex",
+            (HashAlgorithm::Md5, false) => "Prints the MD5 hash in hex",
+            (HashAlgorithm::Blake3, _) => "Prints the BLAKE3 hash in hex",
+            (HashAlgorithm::Crc32, _) => "Prints the CRC-32 checksum in hex",
+            (HashAlgorithm::Sha256, true) => "Prints the HMAC-SHA-256 keyed by <key> in hex",
+            (HashAlgorithm::Sha512, true) => "Prints the HMAC-SHA-512 keyed by <key> in hex",
+            (HashAlgorithm::Sha1, true) => "Prints the HMAC-SHA-1 keyed by <key> in hex",
+            (HashAlgorithm::Md5, true) => "Prints the HMAC-MD5 keyed by <key> in hex",
+        }
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        match self.hmac {
+            true => &[KEY],
+            false => &[],
+        }
+    }
+
+    /// The key of the HMAC variants, without it the one of `--key` is used.
+    fn with_arguments(&self, arguments: &[String]) -> Result<Box<dyn Transmute>> {
+        let key = match (self.hmac, arguments) {
+            (true, []) => self.options.key.clone(),
+            (true, [key]) => Some(key.clone()),
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "The {} transmutation takes no arguments, provided: {}",
+                    self.name(),
+                    arguments.join(" ")
+                )))
+            }
+        };
+        Ok(Box::new(Self {
+            algorithm: self.algorithm,
+            source: self.source,
+            hmac: true,
+            options: HashOptions { key },
+        }))
+    }
+
+    fn transmute(&self, input: &str) -> Result<String> {
+        match self.source {
+            Source::Text => self.hash(&self.options, &mut input.as_bytes()),
+            Source::File => self.hash_file(input),
+        }
+    }
+
+    fn transmute_stream(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<()> {
+        let result = match self.source {
+            Source::Text => self.hash(&self.options, input)?,
+            Source::File => self.hash_file(&read_text(input)?)?,
+        };
+        output.write_all(result.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(name: &str, input: &str) -> String {
+        HashTransmutation::all_variants(Source::Text)
+            .into_iter()
+            .find(|t| t.name() == name)
+            .unwrap()
+            .with_options(HashOptions {
+                key: Some("key".to_string()),
+            })
+            .transmute(input)
+            .unwrap()
+    }
+
+    #[test]
+    fn hashing_text() {
+        let input = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            hash("sha256", input),
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592"
+        );
+        assert_eq!(&hash("sha512", input)[..16], "07e547d9586f6a73");
+        assert_eq!(
+            hash("sha1", input),
+            "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"
+        );
+        assert_eq!(hash("md5", input), "9e107d9d372bb6826bd81d3542a419d6");
+        assert_eq!(
+            hash("blake3", ""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(hash("crc32", input), "414fa339");
+    }
+
+    #[test]
+    fn hashing_with_hmac() {
+        let input = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            hash("hmac-sha256", input),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(hash("hmac-md5", input), "80070713463e7749b90c2dc24911e275");
+
+        let err = HashTransmutation::hmac(HashAlgorithm::Sha1, Source::Text)
+            .unwrap()
+            .transmute(input)
+            .unwrap_err();
+        assert!(err.to_string().contains("--key"));
+
+        let registry = crate::Registry::new();
+        let hmac = registry.pipeline("hmac-sha256 key").unwrap();
+        assert_eq!(
+            hmac.transmute(input).unwrap(),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(
+            registry.find("hmac-sha256").unwrap().usage(),
+            "hmac-sha256 <key>"
+        );
+        assert!(registry.pipeline("sha256 key").is_err());
+        assert!(HashTransmutation::hmac(HashAlgorithm::Crc32, Source::Text).is_none());
+    }
+
+    #[test]
+    fn hashing_file() {
+        let content = std::fs::read_to_string("examples/data.csv").unwrap();
+        let text = HashTransmutation::new(HashAlgorithm::Md5, Source::Text);
+        let file = HashTransmutation::new(HashAlgorithm::Md5, Source::File);
+        assert_eq!(
+            file.transmute("examples/data.csv\n").unwrap(),
+            text.transmute(&content).unwrap()
+        );
+
+        let options = HashOptions {
+            key: Some("the key".to_string()),
+        };
+        let text = HashTransmutation::hmac(HashAlgorithm::Sha256, Source::Text)
+            .unwrap()
+            .with_options(options);
+        let file = HashTransmutation::hmac(HashAlgorithm::Sha256, Source::File).unwrap();
+        assert_eq!(
+            file.transmute("--key 'the key' examples/data.csv").unwrap(),
+            text.transmute(&content).unwrap()
+        );
+    }
+
+    #[test]
+    fn streaming_binary_input() {
+        let mut output = Vec::new();
+        HashTransmutation::new(HashAlgorithm::Crc32, Source::Text)
+            .transmute_stream(&mut &b"\xff\xfe"[..], &mut output)
+            .unwrap();
+        assert_eq!(output.len(), 8);
+    }
+
+    #[test]
+    fn parsing_options() {
+        let args = ["--key".to_string(), "secret".to_string()];
+        assert_eq!(
+            HashOptions::from_args(&args).unwrap().key.as_deref(),
+            Some("secret")
+        );
+        assert!(HashOptions::from_args(&["--format=json".to_string()]).is_err());
+        assert!(HashOptions::from_args(&["--key".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+use colored::{ColoredString, Colorize};
+use prettytable::{format, Cell, Row, Table};
+use pulldown_cmark::{html, Alignment, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use unicode_width::UnicodeWidthStr;
+
+use crate::error::{Error, Result};
+
+/// Output of the `markdown` transmutation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MarkdownFormat {
+    Html,
+    /// Text styled with ANSI escape codes for the terminal. The styles follow
+    /// the `colored` settings, so the plain text is left when colors are off.
+    Ansi,
+}
+
+impl MarkdownFormat {
+    pub fn from_argument(transmutation: &str, argument: &str) -> Result<Self> {
+        match argument {
+            "html" => Ok(Self::Html),
+            "ansi" => Ok(Self::Ansi),
+            _ => Err(Error::InvalidArgument(format!(
+                "The {transmutation} argument <format> requires html or ansi, provided: {argument}"
+            ))),
+        }
+    }
+}
+
+/// Renders the CommonMark text, including the tables and strikethrough of GitHub.
+pub fn render(text: &str, format: MarkdownFormat) -> String {
+    match format {
+        MarkdownFormat::Html => {
+            let mut output = String::new();
+            html::push_html(&mut output, parser(text));
+            output
+        }
+        MarkdownFormat::Ansi => {
+            render_ansi(text, colored::control::SHOULD_COLORIZE.should_colorize())
+        }
+    }
+}
+
+fn parser(text: &str) -> Parser<'_> {
+    Parser::new_ext(text, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH)
+}
+
+fn render_ansi(text: &str, colors: bool) -> String {
+    let mut renderer = AnsiRenderer {
+        colors,
+        ..AnsiRenderer::default()
+    };
+    parser(text).for_each(|event| renderer.event(event));
+    renderer.output.trim_end().to_string()
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Bold,
+    Italic,
+    Strikethrough,
+    Heading(HeadingLevel),
+    Link,
+}
+
+/// Table collected until its end, as its columns are as wide as the widest cell.
+#[derive(Default)]
+struct TableBuffer {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    row: Vec<String>,
+    cell: String,
+}
+
+#[derive(Default)]
+struct AnsiRenderer {
+    /// Whether the styles are written, otherwise the plain text is left.
+    colors: bool,
+    output: String,
+    styles: Vec<Style>,
+    /// The next number of the ordered lists, `None` for the bullet ones.
+    lists: Vec<Option<u64>>,
+    quotes: usize,
+    links: Vec<String>,
+    heading: String,
+    table: Option<TableBuffer>,
+    code_block: bool,
+}
+
+impl AnsiRenderer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.code_block => {
+                for line in text.lines() {
+                    let line = self.paint(line.green());
+                    self.write(&format!("    {line}\n"));
+                }
+            }
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => self.inline(&code, self.paint(code.yellow())),
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.write("\n"),
+            Event::Rule => {
+                let rule = self.paint("─".repeat(40).dimmed());
+                self.write(&format!("{rule}\n\n"));
+            }
+            Event::TaskListMarker(checked) => self.text(if checked { "[x] " } else { "[ ] " }),
+            Event::Html(html) | Event::InlineHtml(html) => self.text(&html),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => self.styles.push(Style::Heading(level)),
+            Tag::Emphasis => self.styles.push(Style::Italic),
+            Tag::Strong => self.styles.push(Style::Bold),
+            Tag::Strikethrough => self.styles.push(Style::Strikethrough),
+            Tag::Link { dest_url, .. } => {
+                self.styles.push(Style::Link);
+                self.links.push(dest_url.to_string());
+            }
+            Tag::BlockQuote(_) => self.quotes += 1,
+            Tag::CodeBlock(_) => self.code_block = true,
+            Tag::List(start) => {
+                self.new_line();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.new_line();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_string(),
+                };
+                let marker = self.paint(marker.bold());
+                self.write(&format!("{indent}{marker} "));
+            }
+            Tag::Table(alignments) => {
+                self.table = Some(TableBuffer {
+                    alignments,
+                    ..TableBuffer::default()
+                })
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph if self.lists.is_empty() => self.write("\n\n"),
+            TagEnd::Paragraph => self.new_line(),
+            TagEnd::Heading(level) => {
+                let heading = std::mem::take(&mut self.heading);
+                let styled = self.paint(self.styled(&heading));
+                self.styles.pop();
+                let underline = match level {
+                    HeadingLevel::H1 => "═",
+                    HeadingLevel::H2 => "─",
+                    _ => "",
+                };
+                self.write(&format!("{styled}\n"));
+                if !underline.is_empty() {
+                    self.write(&format!("{}\n", underline.repeat(heading.width())));
+                }
+                self.write("\n");
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                if let Some(url) = self.links.pop() {
+                    let link = format!(" ({url})");
+                    self.inline(&link, format!(" ({})", self.paint(url.dimmed())));
+                }
+            }
+            TagEnd::BlockQuote(_) => self.quotes -= 1,
+            TagEnd::CodeBlock => {
+                self.code_block = false;
+                self.write("\n");
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.new_line();
+                    self.write("\n");
+                }
+            }
+            TagEnd::TableCell => {
+                if let Some(table) = &mut self.table {
+                    let cell = std::mem::take(&mut table.cell);
+                    table.row.push(cell);
+                }
+            }
+            TagEnd::TableHead | TagEnd::TableRow => {
+                if let Some(table) = &mut self.table {
+                    let row = std::mem::take(&mut table.row);
+                    table.rows.push(row);
+                }
+            }
+            TagEnd::Table => {
+                if let Some(table) = self.table.take() {
+                    self.write(&format!("{}\n", table.render()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        self.inline(text, self.paint(self.styled(text)));
+    }
+
+    /// Collects the inline text into the table cell or the heading being
+    /// rendered, which are styled as a whole, otherwise writes its styled form.
+    fn inline(&mut self, text: &str, styled: String) {
+        if let Some(table) = &mut self.table {
+            table.cell.push_str(text);
+        } else if matches!(self.styles.first(), Some(Style::Heading(_))) {
+            self.heading.push_str(text);
+        } else {
+            self.write(&styled);
+        }
+    }
+
+    fn paint(&self, text: ColoredString) -> String {
+        match self.colors {
+            true => text.to_string(),
+            false => (*text).to_string(),
+        }
+    }
+
+    fn styled(&self, text: &str) -> ColoredString {
+        self.styles
+            .iter()
+            .fold(text.normal(), |text, style| match style {
+                Style::Bold => text.bold(),
+                Style::Italic => text.italic(),
+                Style::Strikethrough => text.strikethrough(),
+                Style::Heading(HeadingLevel::H1) => text.bold().magenta(),
+                Style::Heading(HeadingLevel::H2) => text.bold().cyan(),
+                Style::Heading(_) => text.bold(),
+                Style::Link => text.blue().underline(),
+            })
+    }
+
+    /// Writes the text, the lines in block quotes start with a bar.
+    fn write(&mut self, text: &str) {
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.output.push('\n');
+            }
+            if self.quotes > 0 && !line.is_empty() && self.at_line_start() {
+                let bar = self.paint("│ ".repeat(self.quotes).dimmed());
+                self.output.push_str(&bar);
+            }
+            self.output.push_str(line);
+        }
+    }
+
+    fn new_line(&mut self) {
+        if !self.at_line_start() {
+            self.output.push('\n');
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+}
+
+impl TableBuffer {
+    fn render(&self) -> String {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_BOX_CHARS);
+        let alignment = |index: usize| match self.alignments.get(index) {
+            Some(Alignment::Center) => format::Alignment::CENTER,
+            Some(Alignment::Right) => format::Alignment::RIGHT,
+            _ => format::Alignment::LEFT,
+        };
+        let mut rows = self.rows.iter();
+        if let Some(header) = rows.next() {
+            table.set_titles(Row::new(
+                header
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| Cell::new_align(c, alignment(i)))
+                    .collect(),
+            ));
+        }
+        for row in rows {
+            table.add_row(Row::new(
+                row.iter()
+                    .enumerate()
+                    .map(|(i, c)| Cell::new_align(c, alignment(i)))
+                    .collect(),
+            ));
+        }
+        table.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "\
+# Crabs
+
+Crabs are **bold** and *italic* with `code` and [links](https://rust-lang.org).
+
+- first
+- second
+  1. nested
+
+> quoted
+> text
+
+```rust
+fn main() {}
+```
+
+| Name | Legs |
+|------|-----:|
+| crab |   10 |
+";
+
+    #[test]
+    fn rendering_html() {
+        let html = render(TEXT, MarkdownFormat::Html);
+        assert!(html.starts_with("<h1>Crabs</h1>"));
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<td style=\"text-align: right\">10</td>"));
+    }
+
+    #[test]
+    fn rendering_terminal_text() {
+        let text = render_ansi(TEXT, false);
+        let expected = "\
+Crabs
+═════
+
+Crabs are bold and italic with code and links (https://rust-lang.org).
+
+• first
+• second
+  1. nested
+
+│ quoted text
+
+    fn main() {}
+
+┌──────┬──────┐
+│ Name │ Legs │
+├──────┼──────┤
+│ crab │   10 │
+└──────┴──────┘";
+        assert_eq!(text, expected);
+
+        let text = render_ansi(
+            "# The `foo` function\n\n| a |\n|---|\n| [x](http://y) |\n",
+            false,
+        );
+        let expected = "\
+The foo function
+════════════════
+
+┌──────────────┐
+│ a            │
+├──────────────┤
+│ x (http://y) │
+└──────────────┘";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn parsing_format() {
+        assert_eq!(
+            MarkdownFormat::from_argument("markdown", "ansi").unwrap(),
+            MarkdownFormat::Ansi
+        );
+        assert!(MarkdownFormat::from_argument("markdown", "pdf").is_err());
+    }
+}
@@ -0,0 +1,414 @@
+use regex::Regex;
+
+use crate::{
+    error::{Error, Result},
+    transmute::{
+        arguments::{usage, Parameter},
+        cipher::{caesar, vigenere},
+        markdown::{render, MarkdownFormat},
+        reflow::{break_lines, reflow, Justification},
+        Transmute,
+    },
+};
+
+const WIDTH: Parameter = Parameter::new("width", "number of characters");
+const FILL: Parameter = Parameter::new("fill", "character added up to the width");
+const FROM: Parameter = Parameter::new("from", "text to replace");
+const TO: Parameter = Parameter::new("to", "replacement, '' removes the text");
+const PATTERN: Parameter = Parameter::new("pattern", "regular expression to replace");
+const REPLACEMENT: Parameter = Parameter::new(
+    "replacement",
+    "replacement, $1 or ${name} insert the captured groups",
+);
+const COUNT: Parameter = Parameter::new("count", "number of repetitions");
+const SHIFT: Parameter = Parameter::new("shift", "positions in the alphabet, negative to go back");
+const KEY: Parameter = Parameter::new("key", "letters shifting the text");
+const FORMAT: Parameter = Parameter::new("format", "html, or ansi for the terminal");
+/// Widths beyond any terminal or page, the lines are padded up to them.
+const MAX_WIDTH: usize = 1_000;
+const MAX_COUNT: usize = 10_000;
+/// Size in bytes of the repeated text.
+const MAX_REPEAT_SIZE: usize = 64 * 1024 * 1024;
+const JUSTIFICATION: Parameter = Parameter::new(
+    "justification",
+    "alignment of the lines: left, right, center or full",
+);
+
+/// Built-in transmutations taking arguments after their name, e.g. `truncate 80`.
+///
+/// The variants registered in the [`Registry`](crate::Registry) only describe
+/// the parameters, the stages of a pipeline are configured through
+/// [`Transmute::with_arguments`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParameterizedTransmutation {
+    Truncate,
+    Wrap,
+    Reflow,
+    PadLeft,
+    PadRight,
+    Replace,
+    RegexReplace,
+    Repeat,
+    Caesar,
+    VigenereEncrypt,
+    VigenereDecrypt,
+    Markdown,
+}
+
+impl ParameterizedTransmutation {
+    pub fn all_variants() -> Vec<Self> {
+        vec![
+            Self::Truncate,
+            Self::Wrap,
+            Self::Reflow,
+            Self::PadLeft,
+            Self::PadRight,
+            Self::Replace,
+            Self::RegexReplace,
+            Self::Repeat,
+            Self::Caesar,
+            Self::VigenereEncrypt,
+            Self::VigenereDecrypt,
+            Self::Markdown,
+        ]
+    }
+}
+
+/// Transmutation configured with the arguments of its parameters.
+enum Configured {
+    Truncate(usize),
+    Wrap(usize),
+    Reflow(usize, Justification),
+    PadLeft(usize, char),
+    PadRight(usize, char),
+    Replace(String, String),
+    RegexReplace(Regex, String),
+    Repeat(usize),
+    Caesar(i64),
+    Vigenere(String, bool),
+    Markdown(MarkdownFormat),
+}
+
+struct ConfiguredTransmutation {
+    transmutation: ParameterizedTransmutation,
+    configured: Configured,
+}
+
+impl Transmute for ParameterizedTransmutation {
+    fn name(&self) -> &str {
+        match self {
+            Self::Truncate => "truncate",
+            Self::Wrap => "wrap",
+            Self::Reflow => "reflow",
+            Self::PadLeft => "pad-left",
+            Self::PadRight => "pad-right",
+            Self::Replace => "replace",
+            Self::RegexReplace => "regex-replace",
+            Self::Repeat => "repeat",
+            Self::Caesar => "caesar",
+            Self::VigenereEncrypt => "vigenere-encrypt",
+            Self::VigenereDecrypt => "vigenere-decrypt",
+            Self::Markdown => "markdown",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self {
+            Self::Truncate => "Keeps the first <width> characters",
+            Self::Wrap => "Wraps the lines at the spaces to fit into <width> columns",
+            Self::Reflow => {
+                "Reflows the paragraphs, bullets and quotes to <width> columns with the <justification>"
+            }
+            Self::PadLeft => "Prepends <fill> characters up to <width> characters",
+            Self::PadRight => "Appends <fill> characters up to <width> characters",
+            Self::Replace => "Replaces all occurrences of <from> by <to>",
+            Self::RegexReplace => "Replaces all matches of <pattern> by <replacement>",
+            Self::Repeat => "Repeats the text <count> times",
+            Self::Caesar => "Shifts the letters by <shift> places in the alphabet",
+            Self::VigenereEncrypt => "Encrypts the letters with the Vigenère cipher",
+            Self::VigenereDecrypt => "Decrypts the letters of the Vigenère cipher",
+            Self::Markdown => "Renders the CommonMark text in the <format>",
+        }
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        match self {
+            Self::Truncate | Self::Wrap => &[WIDTH],
+            Self::Reflow => &[WIDTH, JUSTIFICATION],
+            Self::PadLeft | Self::PadRight => &[WIDTH, FILL],
+            Self::Replace => &[FROM, TO],
+            Self::RegexReplace => &[PATTERN, REPLACEMENT],
+            Self::Repeat => &[COUNT],
+            Self::Caesar => &[SHIFT],
+            Self::VigenereEncrypt | Self::VigenereDecrypt => &[KEY],
+            Self::Markdown => &[FORMAT],
+        }
+    }
+
+    fn with_arguments(&self, arguments: &[String]) -> Result<Box<dyn Transmute>> {
+        let name = self.name();
+        let configured = match (self, arguments) {
+            (Self::Truncate, [width]) => Configured::Truncate(WIDTH.number(name, width)?),
+            (Self::Wrap, [width]) => Configured::Wrap(positive_width(name, width)?),
+            (Self::Reflow, [width, justification]) => Configured::Reflow(
+                positive_width(name, width)?,
+                Justification::from_argument(name, justification)?,
+            ),
+            (Self::PadLeft, [width, fill]) => Configured::PadLeft(
+                WIDTH.bounded(name, width, MAX_WIDTH)?,
+                FILL.character(name, fill)?,
+            ),
+            (Self::PadRight, [width, fill]) => Configured::PadRight(
+                WIDTH.bounded(name, width, MAX_WIDTH)?,
+                FILL.character(name, fill)?,
+            ),
+            (Self::Replace, [from, _]) if from.is_empty() => {
+                return Err(Error::InvalidArgument(format!(
+                    "The {name} argument <from> cannot be empty"
+                )))
+            }
+            (Self::Replace, [from, to]) => Configured::Replace(from.clone(), to.clone()),
+            (Self::RegexReplace, [pattern, replacement]) => {
+                Configured::RegexReplace(PATTERN.regex(name, pattern)?, replacement.clone())
+            }
+            (Self::Repeat, [count]) => Configured::Repeat(COUNT.bounded(name, count, MAX_COUNT)?),
+            (Self::Caesar, [shift]) => Configured::Caesar(SHIFT.integer(name, shift)?),
+            (Self::VigenereEncrypt | Self::VigenereDecrypt, [key])
+                if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) =>
+            {
+                return Err(Error::InvalidArgument(format!(
+                    "The {name} argument <key> requires ASCII letters, provided: {key}"
+                )))
+            }
+            (Self::VigenereEncrypt | Self::VigenereDecrypt, [key]) => {
+                Configured::Vigenere(key.clone(), *self == Self::VigenereDecrypt)
+            }
+            (Self::Markdown, [format]) => {
+                Configured::Markdown(MarkdownFormat::from_argument(name, format)?)
+            }
+            _ => return Err(self.missing_arguments(arguments)),
+        };
+        Ok(Box::new(ConfiguredTransmutation {
+            transmutation: *self,
+            configured,
+        }))
+    }
+
+    fn transmute(&self, _input: &str) -> Result<String> {
+        Err(self.missing_arguments(&[]))
+    }
+}
+
+/// Wrapping needs at least one column.
+fn positive_width(transmutation: &str, argument: &str) -> Result<usize> {
+    match WIDTH.bounded(transmutation, argument, MAX_WIDTH)? {
+        0 => Err(Error::InvalidArgument(format!(
+            "The {transmutation} argument <width> requires a positive number, provided: 0"
+        ))),
+        width => Ok(width),
+    }
+}
+
+impl ParameterizedTransmutation {
+    fn missing_arguments(&self, arguments: &[String]) -> Error {
+        Error::InvalidArgument(format!(
+            "The {} transmutation takes {} arguments, provided {}: {}",
+            self.name(),
+            self.parameters().len(),
+            arguments.len(),
+            usage(self.name(), self.parameters())
+        ))
+    }
+}
+
+impl Transmute for ConfiguredTransmutation {
+    fn name(&self) -> &str {
+        self.transmutation.name()
+    }
+
+    fn description(&self) -> &str {
+        self.transmutation.description()
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        self.transmutation.parameters()
+    }
+
+    fn with_arguments(&self, arguments: &[String]) -> Result<Box<dyn Transmute>> {
+        self.transmutation.with_arguments(arguments)
+    }
+
+    /// The ciphers shifting the letters are undone by shifting them back.
+    fn inverse(&self) -> Option<Box<dyn Transmute>> {
+        let (transmutation, configured) = match &self.configured {
+            Configured::Caesar(shift) => (
+                ParameterizedTransmutation::Caesar,
+                Configured::Caesar(-shift.rem_euclid(26)),
+            ),
+            Configured::Vigenere(key, decrypt) => (
+                match decrypt {
+                    true => ParameterizedTransmutation::VigenereEncrypt,
+                    false => ParameterizedTransmutation::VigenereDecrypt,
+                },
+                Configured::Vigenere(key.clone(), !decrypt),
+            ),
+            _ => return None,
+        };
+        Some(Box::new(ConfiguredTransmutation {
+            transmutation,
+            configured,
+        }))
+    }
+
+    fn transmute(&self, input: &str) -> Result<String> {
+        let result = match &self.configured {
+            Configured::Truncate(width) => input.chars().take(*width).collect(),
+            Configured::Wrap(width) => input
+                .split('\n')
+                .map(|line| wrap(line, *width))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            Configured::Reflow(width, justification) => reflow(input, *width, *justification),
+            Configured::PadLeft(width, fill) => {
+                let padding = width.saturating_sub(input.chars().count());
+                std::iter::repeat_n(*fill, padding)
+                    .chain(input.chars())
+                    .collect()
+            }
+            Configured::PadRight(width, fill) => {
+                let padding = width.saturating_sub(input.chars().count());
+                input
+                    .chars()
+                    .chain(std::iter::repeat_n(*fill, padding))
+                    .collect()
+            }
+            Configured::Replace(from, to) => input.replace(from.as_str(), to),
+            Configured::RegexReplace(regex, replacement) => {
+                regex.replace_all(input, replacement.as_str()).into_owned()
+            }
+            Configured::Repeat(count) => match input.len().checked_mul(*count) {
+                Some(size) if size <= MAX_REPEAT_SIZE => input.repeat(*count),
+                _ => {
+                    return Err(Error::InvalidArgument(format!(
+                        "Repeating the text {count} times exceeds {MAX_REPEAT_SIZE} bytes"
+                    )))
+                }
+            },
+            Configured::Caesar(shift) => caesar(input, *shift),
+            Configured::Vigenere(key, decrypt) => vigenere(input, key, *decrypt),
+            Configured::Markdown(format) => render(input, *format),
+        };
+        Ok(result)
+    }
+}
+
+/// Breaks the line at the spaces so the parts fit into the display width. Words
+/// wider than the width are kept on their own line.
+fn wrap(line: &str, width: usize) -> String {
+    break_lines(line.split_whitespace(), width)
+        .iter()
+        .map(|words| words.join(" "))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transmute(
+        transmutation: ParameterizedTransmutation,
+        arguments: &[&str],
+        input: &str,
+    ) -> String {
+        let arguments = arguments.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        transmutation
+            .with_arguments(&arguments)
+            .unwrap()
+            .transmute(input)
+            .unwrap()
+    }
+
+    #[test]
+    fn transmuting_with_arguments() {
+        use ParameterizedTransmutation::*;
+
+        assert_eq!(transmute(Truncate, &["4"], "crabs🦀🦀"), "crab");
+        assert_eq!(transmute(Truncate, &["10"], "crab"), "crab");
+        assert_eq!(transmute(PadLeft, &["5", "0"], "42"), "00042");
+        assert_eq!(transmute(PadRight, &["4", "."], "ab"), "ab..");
+        assert_eq!(transmute(PadLeft, &["1", "0"], "42"), "42");
+        assert_eq!(transmute(Replace, &["foo", "bar"], "foo food"), "bar bard");
+        assert_eq!(transmute(Replace, &[" ", ""], "a b c"), "abc");
+        assert_eq!(transmute(RegexReplace, &[r"\d+", "#"], "a1b22c"), "a#b#c");
+        assert_eq!(
+            transmute(RegexReplace, &[r"(\w+)@(\w+)", "$2 at $1"], "me@home"),
+            "home at me"
+        );
+        assert_eq!(transmute(Repeat, &["3"], "ab"), "ababab");
+    }
+
+    #[test]
+    fn cipher_round_trips() {
+        use ParameterizedTransmutation::*;
+
+        let input = "Rust: Where 'Send' and 'Sync' keep secrets safe!";
+        let shifted = transmute(Caesar, &["3"], input);
+        assert_eq!(shifted, "Uxvw: Zkhuh 'Vhqg' dqg 'Vbqf' nhhs vhfuhwv vdih!");
+        assert_eq!(transmute(Caesar, &["-3"], &shifted), input);
+
+        let encrypted = transmute(VigenereEncrypt, &["Ferris"], input);
+        assert_eq!(transmute(VigenereDecrypt, &["Ferris"], &encrypted), input);
+    }
+
+    #[test]
+    fn wrapping_lines() {
+        let result = transmute(
+            ParameterizedTransmutation::Wrap,
+            &["10"],
+            "the quick brown fox jumps\nover extraordinarily lazy dogs",
+        );
+        assert_eq!(
+            result,
+            "the quick\nbrown fox\njumps\nover\nextraordinarily\nlazy dogs"
+        );
+    }
+
+    #[test]
+    fn validating_arguments() {
+        use ParameterizedTransmutation::*;
+
+        let configure = |transmutation: ParameterizedTransmutation, arguments: &[&str]| {
+            let arguments = arguments.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+            transmutation
+                .with_arguments(&arguments)
+                .err()
+                .map(|e| e.to_string())
+        };
+        assert_eq!(
+            configure(PadLeft, &["10"]).unwrap(),
+            "The pad-left transmutation takes 2 arguments, provided 1: pad-left <width> <fill>"
+        );
+        assert!(configure(Truncate, &["many"]).is_some());
+        assert!(configure(Wrap, &["0"]).is_some());
+        assert!(configure(Reflow, &["72", "justify"]).is_some());
+        assert!(configure(Caesar, &["three"]).is_some());
+        assert!(configure(VigenereEncrypt, &["cr4b"]).is_some());
+        assert!(configure(PadLeft, &["10", "ab"]).is_some());
+        assert!(configure(Replace, &["", "x"]).is_some());
+        assert!(configure(RegexReplace, &["(", "x"]).is_some());
+        assert!(Repeat.transmute("text").is_err());
+        assert_eq!(
+            configure(Repeat, &["100000000000000"]).unwrap(),
+            "The repeat argument <count> requires a number up to 10000, provided: 100000000000000"
+        );
+        assert!(configure(Repeat, &["18446744073709551615"]).is_some());
+        assert!(configure(PadRight, &["1000000000000", "-"]).is_some());
+        assert!(configure(Reflow, &["1000000000000", "center"]).is_some());
+        assert!(configure(Wrap, &["1001"]).is_some());
+
+        let repeat = Repeat.with_arguments(&["10000".to_string()]).unwrap();
+        assert!(repeat.transmute(&"crab".repeat(100)).is_ok());
+        assert!(repeat.transmute(&"crab".repeat(2000000)).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    io::{Read, Write},
+    ops::Deref,
+};
+
+use crate::{
+    error::{Error, Result},
+    registry::Registry,
+    transmute::{arguments::split_stages, read_text, Transmute},
+};
+
+/// Transmutation of a pipeline stage, either the registered one or its copy
+/// configured with the arguments following the name.
+pub enum Stage<'a> {
+    Registered(&'a dyn Transmute),
+    Configured(Box<dyn Transmute>),
+}
+
+impl<'a> Deref for Stage<'a> {
+    type Target = dyn Transmute + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Registered(transmutation) => *transmutation,
+            Self::Configured(transmutation) => transmutation.as_ref(),
+        }
+    }
+}
+
+impl<'a> Stage<'a> {
+    /// The stage undoing this one, see [`Transmute::invert`].
+    pub fn invert(&self) -> Result<Stage<'a>> {
+        Ok(Self::Configured(Transmute::invert(&**self)?))
+    }
+}
+
+/// Ordered sequence of transmutations such as `lowercase|truncate 80|slugify`.
+/// The output of every stage is used as the input of the next one. The arguments
+/// of a stage are separated by whitespace, the ones containing whitespace or `|`
+/// are written in quotes, e.g. `replace ' ' '|'`.
+pub struct Pipeline<'a> {
+    stages: Vec<Stage<'a>>,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn from_str(registry: &'a Registry, text: &str) -> Result<Self> {
+        let stages = split_stages(text)?
+            .iter()
+            .map(|stage| registry.stage(&stage[0], &stage[1..]))
+            .collect::<Result<Vec<Stage>>>()?;
+
+        Ok(Self { stages })
+    }
+
+    /// The pipeline undoing this one, the stages are inverted in the reverse
+    /// order. Fails if any of them is not reversible.
+    pub fn invert(&self) -> Result<Self> {
+        let stages = self
+            .stages
+            .iter()
+            .rev()
+            .map(Stage::invert)
+            .collect::<Result<Vec<Stage>>>()?;
+
+        Ok(Self { stages })
+    }
+
+    pub fn transmute(&self, input: &str) -> Result<String> {
+        self.run(&self.stages, input)
+    }
+
+    /// Writes the result of the last stage directly into the output.
+    pub fn transmute_to(&self, input: &str, output: &mut dyn Write) -> Result<()> {
+        let Some((last, stages)) = self.stages.split_last() else {
+            output.write_all(input.as_bytes())?;
+            return Ok(());
+        };
+        let text = self.run(stages, input)?;
+        last.transmute_to(&text, output)
+            .map_err(|e| self.stage_error(stages.len(), e))
+    }
+
+    /// Single stage pipelines read the input incrementally if the stage supports
+    /// it. Otherwise the whole input is read first.
+    pub fn transmute_stream(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<()> {
+        if let [stage] = &self.stages[..] {
+            return stage
+                .transmute_stream(input, output)
+                .map_err(|e| self.stage_error(0, e));
+        }
+        self.transmute_to(&read_text(input)?, output)
+    }
+
+    fn run(&self, stages: &[Stage], input: &str) -> Result<String> {
+        let mut text = input.to_string();
+        for (index, stage) in stages.iter().enumerate() {
+            text = stage
+                .transmute(&text)
+                .map_err(|e| self.stage_error(index, e))?;
+        }
+
+        Ok(text)
+    }
+
+    fn stage_error(&self, index: usize, error: Error) -> Error {
+        if self.stages.len() == 1 {
+            return error;
+        }
+        Error::Pipeline {
+            stage: index + 1,
+            name: self.stages[index].name().to_string(),
+            source: Box::new(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transmute::csv::{CsvSource, CsvTransmutation};
+
+    use super::*;
+
+    #[test]
+    fn runs_stages_in_order() {
+        let registry = Registry::new();
+        let pipeline = registry.pipeline("lowercase|reverse-talk|slugify").unwrap();
+        let result = pipeline.transmute("Rust Is FUN").unwrap();
+        assert_eq!(result, "tsur-si-nuf");
+    }
+
+    #[test]
+    fn single_stage() {
+        let registry = Registry::new();
+        let pipeline = registry.pipeline("uppercase").unwrap();
+        assert_eq!(pipeline.transmute("crab").unwrap(), "CRAB");
+    }
+
+    #[test]
+    fn inverting_stages() {
+        let registry = Registry::new();
+        let pipeline = registry.pipeline("caesar 3|reverse-talk").unwrap();
+        let inverse = pipeline.invert().unwrap();
+        assert_eq!(pipeline.transmute("Crab").unwrap(), "eduF");
+        assert_eq!(inverse.transmute("eduF").unwrap(), "Crab");
+
+        let err = registry.pipeline("caesar 3|slugify").unwrap().invert();
+        assert!(err.err().unwrap().to_string().contains("slugify"));
+    }
+
+    #[test]
+    fn unknown_stage() {
+        let registry = Registry::new();
+        let err = registry.pipeline("lowercase|shout").err().unwrap();
+        assert!(err.to_string().contains("shout"));
+    }
+
+    #[test]
+    fn stages_with_arguments() {
+        let registry = Registry::new();
+        let pipeline = registry
+            .pipeline(r"regex-replace '\d+' '#' | truncate 6|pad-right 8 .")
+            .unwrap();
+        assert_eq!(pipeline.transmute("ab12cd345").unwrap(), "ab#cd#..");
+
+        let err = registry.pipeline("lowercase|truncate").err().unwrap();
+        assert!(err.to_string().contains("truncate <width>"));
+        assert!(registry.pipeline("lowercase 80").is_err());
+    }
+
+    #[test]
+    fn empty_stage() {
+        let registry = Registry::new();
+        assert!(registry.pipeline("lowercase||slugify").is_err());
+    }
+
+    #[test]
+    fn streams_last_stage() {
+        let mut registry = Registry::new();
+        registry.register(CsvTransmutation::new(CsvSource::File));
+        let pipeline = registry.pipeline("lowercase|csv").unwrap();
+        let mut output = Vec::new();
+        pipeline
+            .transmute_to("--format TSV examples/data.csv", &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output).unwrap().starts_with("Name\tAge"));
+    }
+
+    #[test]
+    fn reports_failing_stage() {
+        let mut registry = Registry::new();
+        registry.register(CsvTransmutation::new(CsvSource::File));
+        let pipeline = registry.pipeline("lowercase|csv").unwrap();
+        let err = pipeline.transmute("missing.csv").err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("Stage 2 (csv) of the pipeline failed"));
+    }
+}
@@ -0,0 +1,264 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::error::{Error, Result};
+
+/// Alignment of the reflowed lines within the width.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Justification {
+    Left,
+    Right,
+    Center,
+    /// Stretches the spaces between the words to fill the width, the last line
+    /// of a paragraph is aligned to the left.
+    Full,
+}
+
+impl Justification {
+    pub fn from_argument(transmutation: &str, argument: &str) -> Result<Self> {
+        match argument {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "center" => Ok(Self::Center),
+            "full" => Ok(Self::Full),
+            _ => Err(Error::InvalidArgument(format!(
+                "The {transmutation} argument <justification> requires left, right, center or full, provided: {argument}"
+            ))),
+        }
+    }
+}
+
+/// Consecutive lines joined into one and broken again at the width. The first
+/// line starts with the prefix of the bullet, the following ones are indented
+/// to the text of the bullet.
+struct Paragraph {
+    quote: String,
+    first_prefix: String,
+    prefix: String,
+    bullet: bool,
+    words: Vec<String>,
+}
+
+/// Reflows the paragraphs of the text to fit into the display width, so wide
+/// characters such as CJK and emoji count twice. Blank lines separating the
+/// paragraphs are kept, as well as the indentation of the bullets (`-`, `*`, `+`,
+/// `1.` or `1)`) and the `>` markers of quoted blocks. Words wider than the
+/// width are kept on their own line.
+pub fn reflow(text: &str, width: usize, justification: Justification) -> String {
+    let mut lines = Vec::new();
+    let mut paragraph: Option<Paragraph> = None;
+
+    for line in text.lines() {
+        let (quote, content) = split_quote(line);
+        if content.trim().is_empty() {
+            if let Some(p) = paragraph.take() {
+                p.layout(width, justification, &mut lines);
+            }
+            lines.push(quote.trim_end().to_string());
+            continue;
+        }
+
+        let rest = content.trim_start();
+        let indent = &content[..content.len() - rest.len()];
+        let words = rest.split_whitespace().map(String::from);
+        match (&mut paragraph, bullet_marker(rest)) {
+            (Some(p), None) if p.quote == quote && (!p.bullet || !indent.is_empty()) => {
+                p.words.extend(words);
+            }
+            (_, marker) => {
+                if let Some(p) = paragraph.take() {
+                    p.layout(width, justification, &mut lines);
+                }
+                let (first_prefix, prefix) = match marker {
+                    Some(marker) => (
+                        format!("{quote}{indent}{marker} "),
+                        format!("{quote}{indent}{}", " ".repeat(marker.width() + 1)),
+                    ),
+                    None => (format!("{quote}{indent}"), format!("{quote}{indent}")),
+                };
+                let words = match marker {
+                    Some(marker) => rest[marker.len()..]
+                        .split_whitespace()
+                        .map(String::from)
+                        .collect(),
+                    None => words.collect(),
+                };
+                paragraph = Some(Paragraph {
+                    quote: quote.to_string(),
+                    first_prefix,
+                    prefix,
+                    bullet: marker.is_some(),
+                    words,
+                });
+            }
+        }
+    }
+    if let Some(p) = paragraph {
+        p.layout(width, justification, &mut lines);
+    }
+
+    let mut result = lines.join("\n");
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+impl Paragraph {
+    fn layout(self, width: usize, justification: Justification, lines: &mut Vec<String>) {
+        let available = width.saturating_sub(self.prefix.width()).max(1);
+        let broken = break_lines(self.words.iter().map(String::as_str), available);
+
+        let count = broken.len();
+        for (index, words) in broken.into_iter().enumerate() {
+            let prefix = match index {
+                0 => &self.first_prefix,
+                _ => &self.prefix,
+            };
+            let last = index + 1 == count;
+            let line = justify(&words, available, justification, last);
+            lines.push(format!("{prefix}{line}").trim_end().to_string());
+        }
+    }
+}
+
+/// Breaks the words into lines filling the display width one after another, a
+/// line always holds at least one word, even a wider one. There is a single
+/// empty line when there are no words.
+pub(crate) fn break_lines<'a>(
+    words: impl IntoIterator<Item = &'a str>,
+    width: usize,
+) -> Vec<Vec<&'a str>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<&str> = Vec::new();
+    let mut line_width = 0;
+    for word in words {
+        let word_width = word.width();
+        if !line.is_empty() && line_width + 1 + word_width > width {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+        line_width += word_width + usize::from(!line.is_empty());
+        line.push(word);
+    }
+    lines.push(line);
+    lines
+}
+
+fn justify(words: &[&str], width: usize, justification: Justification, last: bool) -> String {
+    let line = words.join(" ");
+    let padding = width.saturating_sub(line.width());
+    match justification {
+        Justification::Left => line,
+        Justification::Right => format!("{}{line}", " ".repeat(padding)),
+        Justification::Center => format!("{}{line}", " ".repeat(padding / 2)),
+        Justification::Full if last || words.len() < 2 => line,
+        Justification::Full => {
+            let gaps = words.len() - 1;
+            let mut result = words[0].to_string();
+            for (index, word) in words[1..].iter().enumerate() {
+                // the gaps on the left get the spaces which cannot be spread evenly
+                let spaces = 1 + padding / gaps + usize::from(index < padding % gaps);
+                result.push_str(&" ".repeat(spaces));
+                result.push_str(word);
+            }
+            result
+        }
+    }
+}
+
+/// Splits the `>` markers of a quoted line, with the space following each one,
+/// from its content.
+fn split_quote(line: &str) -> (&str, &str) {
+    let mut rest = line;
+    while let Some(after) = rest.trim_start().strip_prefix('>') {
+        rest = after.strip_prefix(' ').unwrap_or(after);
+    }
+    line.split_at(line.len() - rest.len())
+}
+
+/// The marker of a bullet or numbered list item followed by a space.
+fn bullet_marker(text: &str) -> Option<&str> {
+    let marker = text.split_whitespace().next()?;
+    if !text[marker.len()..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    let numbered = marker
+        .strip_suffix(['.', ')'])
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    (matches!(marker, "-" | "*" | "+") || numbered).then_some(marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflowing_paragraphs() {
+        let text = "The quick brown\nfox jumps over the lazy dog.\n\nSecond paragraph here.\n";
+        assert_eq!(
+            reflow(text, 16, Justification::Left),
+            "The quick brown\nfox jumps over\nthe lazy dog.\n\nSecond paragraph\nhere.\n"
+        );
+    }
+
+    #[test]
+    fn keeping_bullets_and_quotes() {
+        let text = "\
+- first item which is long
+  and continues
+  10. nested numbered item
+> quoted text that
+> wraps around
+>
+> > nested quote";
+        assert_eq!(
+            reflow(text, 14, Justification::Left),
+            "\
+- first item
+  which is
+  long and
+  continues
+  10. nested
+      numbered
+      item
+> quoted text
+> that wraps
+> around
+>
+> > nested
+> > quote"
+        );
+    }
+
+    #[test]
+    fn justifying_lines() {
+        let text = "aa bb cc dd ee";
+        assert_eq!(
+            reflow(text, 9, Justification::Right),
+            " aa bb cc\n    dd ee"
+        );
+        assert_eq!(
+            reflow(text, 10, Justification::Center),
+            " aa bb cc\n  dd ee"
+        );
+        assert_eq!(reflow("a bb cc dd", 8, Justification::Full), "a  bb cc\ndd");
+        assert_eq!(reflow("a b c d", 6, Justification::Full), "a  b c\nd");
+    }
+
+    #[test]
+    fn measuring_display_width() {
+        assert_eq!(
+            reflow("日本語 🦀 crab", 12, Justification::Right),
+            "   日本語 🦀\n        crab"
+        );
+    }
+
+    #[test]
+    fn parsing_justification() {
+        assert_eq!(
+            Justification::from_argument("reflow", "full").unwrap(),
+            Justification::Full
+        );
+        assert!(Justification::from_argument("reflow", "justify").is_err());
+    }
+}
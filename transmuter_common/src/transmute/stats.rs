@@ -0,0 +1,380 @@
+use std::{collections::HashMap, fs};
+
+use prettytable::{format, Cell, Row, Table};
+use serde_json::{json, Value};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    error::{Error, Result},
+    transmute::{
+        arguments::{parse_leading_options, parse_options},
+        Source, Transmute,
+    },
+};
+
+/// Common English words skipped by the top words with `--stop-words skip`.
+const STOP_WORDS: [&str; 48] = [
+    "a", "about", "after", "all", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from",
+    "had", "has", "have", "he", "her", "his", "i", "if", "in", "into", "is", "it", "its", "not",
+    "of", "on", "or", "our", "she", "so", "that", "the", "their", "them", "they", "this", "to",
+    "was", "we", "were", "which", "will", "with", "you",
+];
+
+/// Output format of the `stats` transmutation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatsFormat {
+    Table,
+    Json,
+}
+
+/// Options of the `stats` transmutation in the `--name value` or `--name=value`
+/// form, e.g. `--top 5 --stop-words skip --format json`.
+#[derive(Clone, Debug)]
+pub struct StatsOptions {
+    pub format: StatsFormat,
+    /// Number of the most frequent words reported.
+    pub top: usize,
+    /// Whether the [`STOP_WORDS`] are left out of the most frequent words.
+    pub skip_stop_words: bool,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self {
+            format: StatsFormat::Table,
+            top: 10,
+            skip_stop_words: false,
+        }
+    }
+}
+
+impl StatsOptions {
+    /// Builds the options from CLI arguments. Every argument has to be an option.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut options = Self::default();
+        parse_options("stats", args, |name, value| options.set(name, value))?;
+        Ok(options)
+    }
+
+    /// Consumes the options at the beginning of the input, overriding the current
+    /// values. Returns the updated options and the rest of the input.
+    pub fn parse_leading(mut self, input: &str) -> Result<(Self, &str)> {
+        let rest = parse_leading_options("stats", input, |name, value| self.set(name, value))?;
+        Ok((self, rest))
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match (name, value) {
+            ("format", "table") => self.format = StatsFormat::Table,
+            ("format", "json") => self.format = StatsFormat::Json,
+            ("format", _) => {
+                return Err(Error::InvalidArgument(format!(
+                    "The unsupported stats format provided: {value}.\nFollowing are supported: [table, json]"
+                )))
+            }
+            ("top", _) => {
+                self.top = value.parse().map_err(|_| {
+                    Error::InvalidArgument(format!(
+                        "The stats option --top requires a non-negative number, provided: {value}"
+                    ))
+                })?;
+            }
+            ("stop-words", "keep") => self.skip_stop_words = false,
+            ("stop-words", "skip") => self.skip_stop_words = true,
+            ("stop-words", _) => {
+                return Err(Error::InvalidArgument(format!(
+                    "The stats option --stop-words requires keep or skip, provided: {value}"
+                )))
+            }
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "The unsupported stats option provided: --{name}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Counts and readability scores of a text. The words are the Unicode words,
+/// i.e. without the punctuation, the syllables are estimated for English.
+#[derive(Debug, PartialEq)]
+pub struct TextStats {
+    pub characters: usize,
+    pub graphemes: usize,
+    pub words: usize,
+    pub sentences: usize,
+    pub lines: usize,
+    pub syllables: usize,
+    /// Average number of characters of the words.
+    pub average_word_length: Option<f64>,
+    /// The most frequent lowercase words with their counts.
+    pub top_words: Vec<(String, usize)>,
+    /// Flesch reading ease, the higher the easier to read, mostly 0 to 100.
+    pub reading_ease: Option<f64>,
+    /// Flesch-Kincaid grade level, the US school grade needed to understand the text.
+    pub grade: Option<f64>,
+}
+
+impl TextStats {
+    pub fn analyze(text: &str, options: &StatsOptions) -> Self {
+        let words = text.unicode_words().collect::<Vec<&str>>();
+        // the sentence segmentation panics on an empty text, see `empty_text`
+        let sentences = match words.is_empty() {
+            true => 0,
+            false => text
+                .unicode_sentences()
+                .filter(|s| s.unicode_words().next().is_some())
+                .count(),
+        };
+        let syllables = words.iter().map(|w| syllables(w)).sum::<usize>();
+
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        words
+            .iter()
+            .map(|w| w.to_lowercase())
+            .filter(|w| !options.skip_stop_words || !STOP_WORDS.contains(&w.as_str()))
+            .for_each(|w| *frequencies.entry(w).or_default() += 1);
+        let mut top_words = frequencies.into_iter().collect::<Vec<(String, usize)>>();
+        top_words.sort_by(|(a_word, a_count), (b_word, b_count)| {
+            b_count.cmp(a_count).then(a_word.cmp(b_word))
+        });
+        top_words.truncate(options.top);
+
+        let (reading_ease, grade) = match (words.len(), sentences) {
+            (0, _) | (_, 0) => (None, None),
+            (word_count, sentences) => {
+                let words_per_sentence = word_count as f64 / sentences as f64;
+                let syllables_per_word = syllables as f64 / word_count as f64;
+                (
+                    Some(206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word),
+                    Some(0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59),
+                )
+            }
+        };
+        let letters = words.iter().map(|w| w.chars().count()).sum::<usize>();
+
+        Self {
+            characters: text.chars().count(),
+            graphemes: text.graphemes(true).count(),
+            words: words.len(),
+            sentences,
+            lines: text.lines().count(),
+            syllables,
+            average_word_length: (!words.is_empty()).then(|| letters as f64 / words.len() as f64),
+            top_words,
+            reading_ease,
+            grade,
+        }
+    }
+
+    pub fn export(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Table => self.to_table(),
+            StatsFormat::Json => serde_json::to_string_pretty(&self.to_json()).unwrap_or_default(),
+        }
+    }
+
+    fn to_table(&self) -> String {
+        let optional = |number: Option<f64>| number.map_or("-".to_string(), format_number);
+        let top_words = self
+            .top_words
+            .iter()
+            .map(|(word, count)| format!("{word} ({count})"))
+            .collect::<Vec<String>>();
+        let rows = [
+            ("Characters", self.characters.to_string()),
+            ("Graphemes", self.graphemes.to_string()),
+            ("Words", self.words.to_string()),
+            ("Sentences", self.sentences.to_string()),
+            ("Lines", self.lines.to_string()),
+            ("Syllables", self.syllables.to_string()),
+            ("Average word length", optional(self.average_word_length)),
+            ("Flesch reading ease", optional(self.reading_ease)),
+            ("Flesch-Kincaid grade", optional(self.grade)),
+            ("Top words", top_words.join("\n")),
+        ];
+
+        let mut table = Table::new();
+        table.set_titles(Row::new(vec![Cell::new("Metric"), Cell::new("Value")]));
+        for (metric, value) in rows {
+            // the numbers are aligned to the right, the words to the left
+            let alignment = match metric {
+                "Top words" => format::Alignment::LEFT,
+                _ => format::Alignment::RIGHT,
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(metric),
+                Cell::new_align(&value, alignment),
+            ]));
+        }
+        table.to_string()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let rounded = |number: Option<f64>| number.map(|n| (n * 100.0).round() / 100.0);
+        let top_words = self
+            .top_words
+            .iter()
+            .map(|(word, count)| json!({"word": word, "count": count}))
+            .collect::<Vec<Value>>();
+        json!({
+            "characters": self.characters,
+            "graphemes": self.graphemes,
+            "words": self.words,
+            "sentences": self.sentences,
+            "lines": self.lines,
+            "syllables": self.syllables,
+            "average_word_length": rounded(self.average_word_length),
+            "flesch_reading_ease": rounded(self.reading_ease),
+            "flesch_kincaid_grade": rounded(self.grade),
+            "top_words": top_words,
+        })
+    }
+}
+
+/// Estimates the syllables of an English word by its groups of vowels, a final
+/// silent `e` does not count.
+fn syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouy".contains(c);
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if count > 1 && word.ends_with('e') && !word.ends_with("le") && !word.ends_with("ee") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// Formats the number with at most 2 decimal places, omitting trailing zeros.
+fn format_number(number: f64) -> String {
+    let formatted = format!("{number:.2}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// The `stats` transmutation reporting the [`TextStats`] of the input, or of the
+/// file it is the path of. When reading files, the options can precede the file
+/// path, e.g. `--format json README.md`.
+pub struct StatsTransmutation {
+    source: Source,
+    options: StatsOptions,
+}
+
+impl StatsTransmutation {
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            options: StatsOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: StatsOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Transmute for StatsTransmutation {
+    fn name(&self) -> &str {
+        "stats"
+    }
+
+    fn description(&self) -> &str {
+        "Counts the characters, words and sentences and scores the readability"
+    }
+
+    fn transmute(&self, input: &str) -> Result<String> {
+        let (options, text) = match self.source {
+            Source::Text => (self.options.clone(), input.to_string()),
+            Source::File => {
+                let (options, file_path) = self.options.clone().parse_leading(input)?;
+                (options, fs::read_to_string(file_path.trim())?)
+            }
+        };
+        Ok(TextStats::analyze(&text, &options).export(options.format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The crab walks sideways. The crab is happy!\nIs the crab hungry? Yes.";
+
+    #[test]
+    fn counting_text() {
+        let stats = TextStats::analyze(TEXT, &StatsOptions::default());
+        assert_eq!(stats.characters, 68);
+        assert_eq!(stats.words, 13);
+        assert_eq!(stats.sentences, 4);
+        assert_eq!(stats.lines, 2);
+        assert_eq!(
+            stats.top_words[..2],
+            [("crab".to_string(), 3), ("the".to_string(), 3)]
+        );
+        assert!(stats.reading_ease.unwrap() > 80.0);
+
+        let stats = TextStats::analyze("e\u{301}🦀 ", &StatsOptions::default());
+        assert_eq!((stats.characters, stats.graphemes), (4, 3));
+    }
+
+    #[test]
+    fn skipping_stop_words() {
+        let options = StatsOptions::from_args(&[
+            "--stop-words".to_string(),
+            "skip".to_string(),
+            "--top=2".to_string(),
+        ])
+        .unwrap();
+        let stats = TextStats::analyze(TEXT, &options);
+        assert_eq!(
+            stats.top_words,
+            [("crab".to_string(), 3), ("happy".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn empty_text() {
+        let stats = TextStats::analyze("", &StatsOptions::default());
+        assert_eq!((stats.words, stats.sentences), (0, 0));
+        assert_eq!(stats.reading_ease, None);
+        assert!(stats
+            .export(StatsFormat::Table)
+            .contains("Flesch reading ease"));
+    }
+
+    #[test]
+    fn estimating_syllables() {
+        let counts = ["crab", "happy", "walks", "readable", "make", "the"].map(syllables);
+        assert_eq!(counts, [1, 2, 1, 3, 1, 1]);
+    }
+
+    #[test]
+    fn exporting_json() {
+        let json = TextStats::analyze("Hello crab.", &StatsOptions::default()).to_json();
+        assert_eq!(json["words"], 2);
+        assert_eq!(json["average_word_length"], 4.5);
+        assert_eq!(json["top_words"][0]["word"], "crab");
+    }
+
+    #[test]
+    fn parsing_options() {
+        let (options, rest) = StatsOptions::default()
+            .parse_leading("--format json --top 3 README.md")
+            .unwrap();
+        assert_eq!((options.format, options.top), (StatsFormat::Json, 3));
+        assert_eq!(rest, "README.md");
+        assert!(StatsOptions::from_args(&["--stop-words=drop".to_string()]).is_err());
+        assert!(StatsOptions::from_args(&["--window".to_string(), "2".to_string()]).is_err());
+    }
+}
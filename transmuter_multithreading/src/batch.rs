@@ -0,0 +1,336 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use transmuter_common::error::{Error, Result};
+
+/// Where the batch mode writes the transmuted files.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchOutput {
+    /// Directory mirroring the tree of the inputs.
+    Directory(PathBuf),
+    /// The input files are overwritten.
+    InPlace,
+}
+
+/// Options of the `batch <command> <inputs>...` mode.
+#[derive(Debug, PartialEq)]
+pub struct BatchOptions {
+    pub kind: String,
+    pub inputs: Vec<String>,
+    pub output: BatchOutput,
+}
+
+impl BatchOptions {
+    /// Parses the arguments following `batch`. The output is given by either the
+    /// `--output-dir <dir>` or the `--in-place` option.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut output = None;
+        let mut positional = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let option = match arg.as_str() {
+                "--in-place" => BatchOutput::InPlace,
+                "--output-dir" => match args.next() {
+                    Some(dir) => BatchOutput::Directory(PathBuf::from(dir)),
+                    None => {
+                        return Err(Error::InvalidArgument(
+                            "Missing value for the option --output-dir".to_string(),
+                        ))
+                    }
+                },
+                option if option.starts_with("--") => {
+                    return Err(Error::InvalidArgument(format!(
+                        "The unsupported batch option provided: {option}"
+                    )))
+                }
+                _ => {
+                    positional.push(arg.clone());
+                    continue;
+                }
+            };
+            if output.replace(option).is_some() {
+                return Err(Error::InvalidArgument(
+                    "Only one of the options --output-dir and --in-place can be provided"
+                        .to_string(),
+                ));
+            }
+        }
+
+        let usage = || {
+            Error::InvalidArgument("The batch mode should have following format: batch <command> <file|directory|glob>... --output-dir <dir> | --in-place".to_string())
+        };
+        let Some((kind, inputs)) = positional.split_first().filter(|(_, i)| !i.is_empty()) else {
+            return Err(usage());
+        };
+        Ok(Self {
+            kind: kind.clone(),
+            inputs: inputs.to_vec(),
+            output: output.ok_or_else(usage)?,
+        })
+    }
+}
+
+/// File of the batch with its path relative to the input it was found in, which
+/// determines its place in the output directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchFile {
+    pub source: PathBuf,
+    pub relative: PathBuf,
+}
+
+impl BatchFile {
+    pub fn destination(&self, output: &BatchOutput) -> PathBuf {
+        match output {
+            BatchOutput::Directory(dir) => dir.join(&self.relative),
+            BatchOutput::InPlace => self.source.clone(),
+        }
+    }
+}
+
+/// Expands the inputs into the files to transmute. A file is placed directly
+/// into the output directory, the files of a directory keep their path within
+/// it and the files matching a glob keep their path below the part of the
+/// pattern without wildcards.
+pub fn collect_files(inputs: &[String]) -> Result<Vec<BatchFile>> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_file() {
+            files.push(BatchFile {
+                source: path.to_path_buf(),
+                relative: PathBuf::from(path.file_name().unwrap_or(path.as_os_str())),
+            });
+        } else if path.is_dir() {
+            for entry in WalkDir::new(path).sort_by_file_name() {
+                let entry = entry.map_err(io::Error::from)?;
+                if entry.file_type().is_file() {
+                    files.push(BatchFile {
+                        relative: entry
+                            .path()
+                            .strip_prefix(path)
+                            .unwrap_or(entry.path())
+                            .to_path_buf(),
+                        source: entry.into_path(),
+                    });
+                }
+            }
+        } else if input.contains(['*', '?', '[']) {
+            let base = glob_base(input);
+            let mut matched = false;
+            let sources = glob::glob(input).map_err(|e| {
+                Error::InvalidArgument(format!("The invalid pattern provided: {input}: {e}"))
+            })?;
+            for source in sources {
+                let source = source.map_err(io::Error::from)?;
+                if source.is_file() {
+                    matched = true;
+                    files.push(BatchFile {
+                        relative: source.strip_prefix(&base).unwrap_or(&source).to_path_buf(),
+                        source,
+                    });
+                }
+            }
+            if !matched {
+                return Err(Error::InvalidArgument(format!(
+                    "No files match the pattern provided: {input}"
+                )));
+            }
+        } else {
+            return Err(Error::InvalidArgument(format!(
+                "The input provided does not exist: {input}"
+            )));
+        }
+    }
+
+    files.sort_by(|a, b| a.source.cmp(&b.source));
+    files.dedup_by(|a, b| a.source == b.source);
+    Ok(files)
+}
+
+/// Leading components of the glob pattern without any wildcards.
+fn glob_base(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .filter(|c| *c != Component::CurDir)
+        .collect()
+}
+
+/// Transmutes the content of the source file into the destination. The result
+/// is written into a temporary file first, so the destination is left intact
+/// when the transmutation fails, which matters when transmuting in place. The
+/// file is read as it is, so the hashes take binary files while the text
+/// transmutations report them as invalid UTF-8.
+pub fn transmute_file<F>(processor: &F, kind: &str, source: &Path, destination: &Path) -> Result<()>
+where
+    F: Fn(&str, &mut dyn Read, &mut dyn Write) -> Result<()>,
+{
+    let mut input = BufReader::new(File::open(source)?);
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file_name = destination
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let temporary = destination.with_file_name(format!(".{file_name}.transmuting"));
+    let result = (|| {
+        let mut output = BufWriter::new(File::create(&temporary)?);
+        processor(kind, &mut input, &mut output)?;
+        output.flush()?;
+        Ok(fs::rename(&temporary, destination)?)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    fn tree() -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["a.txt", "docs/b.txt", "docs/nested/c.txt", "docs/d.md"] {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, file).unwrap();
+        }
+        dir
+    }
+
+    fn relative_paths(files: &[BatchFile]) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| f.relative.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn parsing_options() {
+        let options =
+            BatchOptions::from_args(&args("uppercase a.txt docs --output-dir out")).unwrap();
+        assert_eq!(options.kind, "uppercase");
+        assert_eq!(options.inputs, vec!["a.txt", "docs"]);
+        assert_eq!(options.output, BatchOutput::Directory(PathBuf::from("out")));
+
+        let options = BatchOptions::from_args(&args("--in-place lowercase *.txt")).unwrap();
+        assert_eq!(options.output, BatchOutput::InPlace);
+
+        assert!(BatchOptions::from_args(&args("uppercase a.txt")).is_err());
+        assert!(BatchOptions::from_args(&args("uppercase --in-place")).is_err());
+        assert!(BatchOptions::from_args(&args("uppercase a --in-place --output-dir o")).is_err());
+        assert!(BatchOptions::from_args(&args("uppercase a --in-place --force")).is_err());
+    }
+
+    #[test]
+    fn collecting_directories_and_files() {
+        let dir = tree();
+        let root = dir.path().to_string_lossy();
+        let files = collect_files(&[format!("{root}/docs"), format!("{root}/a.txt")]).unwrap();
+        assert_eq!(
+            relative_paths(&files),
+            vec!["a.txt", "b.txt", "d.md", "nested/c.txt"]
+        );
+    }
+
+    #[test]
+    fn collecting_globs() {
+        let dir = tree();
+        let root = dir.path().to_string_lossy();
+        let files = collect_files(&[format!("{root}/docs/**/*.txt")]).unwrap();
+        assert_eq!(relative_paths(&files), vec!["b.txt", "nested/c.txt"]);
+
+        assert!(collect_files(&[format!("{root}/*.csv")]).is_err());
+        assert!(collect_files(&[format!("{root}/missing.txt")]).is_err());
+    }
+
+    #[test]
+    fn transmuting_into_mirrored_tree() {
+        let dir = tree();
+        let root = dir.path().to_string_lossy();
+        let output = BatchOutput::Directory(dir.path().join("out"));
+        let uppercase = |_: &str, input: &mut dyn Read, output: &mut dyn Write| -> Result<()> {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            Ok(output.write_all(text.to_uppercase().as_bytes())?)
+        };
+
+        for file in collect_files(&[format!("{root}/docs")]).unwrap() {
+            transmute_file(
+                &uppercase,
+                "uppercase",
+                &file.source,
+                &file.destination(&output),
+            )
+            .unwrap();
+        }
+        let result = fs::read_to_string(dir.path().join("out/nested/c.txt")).unwrap();
+        assert_eq!(result, "DOCS/NESTED/C.TXT");
+    }
+
+    #[test]
+    fn transmuting_whole_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("crabs.csv");
+        fs::write(&source, "name,legs\nFerris,10").unwrap();
+        let registry = crate::registry(transmuter_common::transmute::Source::Text);
+        let processor = |kind: &str, input: &mut dyn Read, output: &mut dyn Write| {
+            registry.pipeline(kind)?.transmute_stream(input, output)
+        };
+
+        let destination = dir.path().join("out/crabs.csv");
+        transmute_file(&processor, "csv", &source, &destination).unwrap();
+        let table = fs::read_to_string(&destination).unwrap();
+        assert!(table.contains("| Ferris |"), "{table}");
+
+        let destination = dir.path().join("out/crabs.sha256");
+        transmute_file(&processor, "sha256", &source, &destination).unwrap();
+        assert_eq!(
+            fs::read_to_string(&destination).unwrap(),
+            "1c1317b428ccf9e6c72dab6faeac6a571db76a03888a7c28be498d3ab34bbbba"
+        );
+
+        let binary = dir.path().join("crab.bin");
+        fs::write(&binary, b"\xff\x00crab").unwrap();
+        let destination = dir.path().join("out/crab.sha256");
+        transmute_file(&processor, "sha256", &binary, &destination).unwrap();
+        assert_eq!(
+            fs::read_to_string(&destination).unwrap(),
+            "7e846c5f91108f98e80603321c2e5e33dcd3b41bc49ab88698d5759b3bdd30bf"
+        );
+        let err = transmute_file(&processor, "uppercase", &binary, &destination).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidUtf8 { offset: Some(0) }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn failing_in_place_keeps_the_file() {
+        let dir = tree();
+        let source = dir.path().join("a.txt");
+        let failing = |_: &str, _: &mut dyn Read, output: &mut dyn Write| -> Result<()> {
+            output.write_all(b"partial")?;
+            Err(Error::InvalidArgument("failed".to_string()))
+        };
+
+        assert!(transmute_file(&failing, "failing", &source, &source).is_err());
+        assert_eq!(fs::read_to_string(&source).unwrap(), "a.txt");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -1,27 +0,0 @@
-use std::{
-    error::Error,
-    fmt::{Display, Formatter}
-};
-
-pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
-
-#[derive(Debug)]
-pub struct CustomError {
-    message: String,
-}
-
-impl CustomError {
-    pub fn new(message: &str) -> Box<Self> {
-        Box::new(Self {
-            message: message.to_string(),
-        })
-    }
-}
-
-impl Display for CustomError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
-    }
-}
-
-impl Error for CustomError {}
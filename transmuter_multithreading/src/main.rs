@@ -1,30 +1,88 @@
 use std::env;
 
-use error::CustomError;
+use repl::TransmutationUsage;
+use server::{ServeOptions, Server};
 use stdio_processor::StdioProcessor;
-use transmute::Transmutation;
+use transmuter_common::{
+    config::Config,
+    error::ErrorFormat,
+    transmute::{
+        csv::CsvTransmutation, hash::HashTransmutation, stats::StatsTransmutation, Source,
+    },
+    Registry,
+};
 
-mod error;
+mod batch;
+mod repl;
+mod server;
 mod stdio_processor;
-mod transmute;
+mod worker_pool;
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let mut stdio_processor = StdioProcessor::new(args, |kind, input| {
-        let transmutation = Transmutation::from_str(kind).ok_or(CustomError::new(&format!(
-            "The unsupported transmutation provided: {}.\nFollowing are supported: {}",
-            String::from(kind),
-            stringify_possible_transmutations()
-        )))?;
-        transmutation.transmute(input)
-    });
+    if args.get(1).is_some_and(|arg| arg == "serve") {
+        serve(&args[2..]);
+        return;
+    }
+    // the batch mode reads the files itself and passes their content
+    let source = match args.get(1).is_some_and(|arg| arg == "batch") {
+        true => Source::Text,
+        false => Source::File,
+    };
+    let mut registry = registry(source);
+    register_aliases(&mut registry, ErrorFormat::from_args(&args));
+    let transmutations = registry
+        .transmutations()
+        .map(TransmutationUsage::new)
+        .collect();
+
+    // `--invert` undoes the transmutations, e.g. `caesar 3 --invert`, wherever it is
+    let invert = args.iter().any(|arg| arg == "--invert");
+    let args = args.into_iter().filter(|arg| arg != "--invert").collect();
+    let mut stdio_processor = StdioProcessor::new(args, move |kind, input, output| {
+        let pipeline = match invert {
+            true => registry.pipeline(kind)?.invert()?,
+            false => registry.pipeline(kind)?,
+        };
+        pipeline.transmute_stream(input, output)
+    })
+    .with_transmutations(transmutations);
     stdio_processor.run();
 }
 
-fn stringify_possible_transmutations() -> String {
-    let transmutations = Transmutation::all_variants()
-        .iter()
-        .map(|v| v.to_string())
-        .collect::<Vec<String>>()
-        .join(", ");
-    format!("[{transmutations}]")
+/// The `serve [--host <host>] [--port <port>] [--workers <count>] [--max-body-size <bytes>]
+/// [--error-format text|json]` mode. Unlike on the command line, the csv, hash and stats transmutations take the content
+/// itself rather than a path, so clients cannot read the files of the server.
+fn serve(args: &[String]) {
+    let error_format = ErrorFormat::from_args(args);
+    let mut registry = registry(Source::Text);
+    register_aliases(&mut registry, error_format);
+
+    let server = ServeOptions::from_args(args)
+        .and_then(|options| Server::bind(options, registry))
+        .unwrap_or_else(|e| error_format.exit(&e));
+    if let Some(address) = server.local_addr() {
+        println!("Listening on http://{address}");
+    }
+    server.run();
+}
+
+/// The built-in transmutations, the csv, hash and stats ones taking the content
+/// from the source.
+fn registry(source: Source) -> Registry {
+    let mut registry = Registry::new();
+    registry.register(CsvTransmutation::new(source));
+    registry.register(CsvTransmutation::describe(source));
+    HashTransmutation::all_variants(source)
+        .into_iter()
+        .for_each(|t| registry.register(t));
+    registry.register(StatsTransmutation::new(source));
+    registry
+}
+
+/// The aliases of the user config expand into the transmutations registered before.
+fn register_aliases(registry: &mut Registry, error_format: ErrorFormat) {
+    Config::load()
+        .and_then(|config| registry.register_aliases(&config.aliases))
+        .unwrap_or_else(|e| error_format.exit(&e));
 }
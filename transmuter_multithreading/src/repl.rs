@@ -0,0 +1,343 @@
+use std::{env, path::PathBuf};
+
+use rustyline::{
+    completion::{Completer, Pair},
+    Context, Helper, Highlighter, Hinter, Validator,
+};
+
+use transmuter_common::{
+    error::{Error, Result},
+    transmute::arguments::quote,
+    Transmute,
+};
+
+pub const PROMPT: &str = ">> ";
+pub const CONTINUATION_PROMPT: &str = ".. ";
+
+/// Commands of the REPL other than the transmutations.
+const BUILT_IN_COMMANDS: [&str; 3] = ["help", "list", "exit"];
+
+pub const HELP: &str = "\
+Usage: <command> <input>
+  <command>            transmutation, or several of them chained with | (e.g. lowercase|slugify)
+                       followed by its arguments (e.g. lowercase|truncate 5 hello world)
+  <input>              text to transmute, taken as it is typed including the quotes and spaces
+  <command> <<END      transmutes the following lines up to the line END
+  list                 lists the available transmutations with their arguments
+  help                 prints this help
+  exit                 quits after the running jobs finish
+  exit --cancel        quits and drops the running jobs
+Press Tab to complete the transmutation names.";
+
+/// Name and usage of a transmutation available in the REPL.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransmutationUsage {
+    pub name: String,
+    /// The name followed by the parameters, e.g. `truncate <width>`.
+    pub usage: String,
+    /// Number of arguments following the name.
+    pub arity: usize,
+}
+
+impl TransmutationUsage {
+    pub fn new(transmutation: &dyn Transmute) -> Self {
+        Self {
+            name: transmutation.name().to_string(),
+            usage: transmutation.usage(),
+            arity: transmutation.parameters().len(),
+        }
+    }
+}
+
+/// Line entered into the REPL.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    List,
+    Exit {
+        cancel: bool,
+    },
+    Transmute {
+        kind: String,
+        text: String,
+    },
+    /// Transmutation of the lines following up to the one with the delimiter.
+    Heredoc {
+        kind: String,
+        delimiter: String,
+    },
+}
+
+impl Command {
+    /// Parses the line, returns `None` for a blank one. The arguments of the last
+    /// transmutation of the command follow it unless it is quoted together with
+    /// them, e.g. `"truncate 5|uppercase" text`. The rest of the line is the text,
+    /// passed on as it was typed.
+    pub fn parse(line: &str, transmutations: &[TransmutationUsage]) -> Result<Option<Self>> {
+        let Some((kind, mut rest)) = next_arg(line)? else {
+            return Ok(None);
+        };
+
+        let last = kind.rsplit('|').next().unwrap_or_default().trim();
+        let arity = transmutations
+            .iter()
+            .find(|t| t.name == last)
+            .map_or(0, |t| t.arity);
+        let mut kind = kind.clone();
+        for _ in 0..arity {
+            let Some((argument, remainder)) = next_arg(rest)? else {
+                break;
+            };
+            kind = format!("{kind} {}", quote(&argument));
+            rest = remainder;
+        }
+        let text = rest.trim_start();
+
+        let command = match (kind.as_str(), text.trim_end()) {
+            ("help", "") => Self::Help,
+            ("list", "") => Self::List,
+            ("exit", "") => Self::Exit { cancel: false },
+            ("exit", "--cancel") => Self::Exit { cancel: true },
+            ("help" | "list" | "exit", _) => {
+                return Err(Error::InvalidArgument(format!(
+                    "Unexpected arguments provided to the command {kind}"
+                )))
+            }
+            (_, heredoc)
+                if heredoc.len() > 2
+                    && heredoc.starts_with("<<")
+                    && !heredoc.contains(char::is_whitespace) =>
+            {
+                Self::Heredoc {
+                    delimiter: heredoc[2..].to_string(),
+                    kind,
+                }
+            }
+            _ => Self::Transmute {
+                kind,
+                text: text.to_string(),
+            },
+        };
+
+        Ok(Some(command))
+    }
+}
+
+/// Splits off the first whitespace separated argument, returns `None` when only
+/// whitespace is left. Text in single or double quotes is kept together with its
+/// whitespace, `\` escapes the next character outside single quotes. The rest
+/// of the line is returned untouched.
+fn next_arg(line: &str) -> Result<Option<(String, &str)>> {
+    let line = line.trim_start();
+    let mut arg: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => arg.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let (_, escaped) = chars.next().ok_or(Error::InvalidArgument(
+                    "The input ends with an unfinished escape sequence".to_string(),
+                ))?;
+                arg.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(_), c) => arg.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                arg.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => return Ok(arg.map(|arg| (arg, &line[index..]))),
+            (None, c) => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if let Some(q) = quote {
+        return Err(Error::InvalidArgument(format!(
+            "The input contains an unclosed quote {q}"
+        )));
+    }
+    Ok(arg.map(|arg| (arg, "")))
+}
+
+/// File the REPL history is kept in, `$TRANSMUTER_HISTORY` or
+/// `~/.transmuter_history`.
+pub fn history_path() -> Option<PathBuf> {
+    env::var_os("TRANSMUTER_HISTORY")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".transmuter_history")))
+}
+
+/// Completes the transmutation names, including the ones chained with `|`.
+#[derive(Helper, Highlighter, Hinter, Validator)]
+pub struct ReplHelper {
+    names: Vec<String>,
+}
+
+impl ReplHelper {
+    pub fn new(transmutations: &[TransmutationUsage]) -> Self {
+        let mut names = transmutations
+            .iter()
+            .map(|t| t.name.clone())
+            .collect::<Vec<String>>();
+        names.extend(BUILT_IN_COMMANDS.map(String::from));
+        Self { names }
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let command = &line[..pos];
+        // only the command at the beginning of the line is completed
+        if command.trim_start().contains(char::is_whitespace) {
+            return Ok((pos, Vec::new()));
+        }
+        let start = command
+            .rfind(|c: char| c == '|' || c.is_whitespace())
+            .map_or(0, |i| i + 1);
+        let prefix = &command[start..];
+
+        let candidates = self
+            .names
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| Pair {
+                display: name.clone(),
+                replacement: name.clone(),
+            })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustyline::history::DefaultHistory;
+
+    use super::*;
+
+    fn split_args(line: &str) -> Result<Vec<String>> {
+        let mut args = Vec::new();
+        let mut rest = line;
+        while let Some((arg, remainder)) = next_arg(rest)? {
+            args.push(arg);
+            rest = remainder;
+        }
+        Ok(args)
+    }
+
+    #[test]
+    fn splitting_quoted_args() {
+        assert_eq!(
+            split_args(r#"uppercase "  a   b " c\ d 'e "f"'"#).unwrap(),
+            vec!["uppercase", "  a   b ", "c d", "e \"f\""]
+        );
+        assert_eq!(split_args(r#"x "" y"#).unwrap(), vec!["x", "", "y"]);
+        assert!(split_args("uppercase \"abc").is_err());
+        assert!(split_args("uppercase abc\\").is_err());
+    }
+
+    fn usage(name: &str, arity: usize) -> TransmutationUsage {
+        TransmutationUsage {
+            name: name.to_string(),
+            usage: name.to_string(),
+            arity,
+        }
+    }
+
+    #[test]
+    fn parsing_commands() {
+        let parse = |line: &str| Command::parse(line, &[]);
+        assert_eq!(parse("   ").unwrap(), None);
+        assert_eq!(parse("help").unwrap(), Some(Command::Help));
+        assert_eq!(parse("list").unwrap(), Some(Command::List));
+        assert_eq!(
+            parse("exit --cancel").unwrap(),
+            Some(Command::Exit { cancel: true })
+        );
+        assert!(parse("exit now").is_err());
+        assert_eq!(
+            parse("lowercase|slugify  Hello   \"big  world\" ").unwrap(),
+            Some(Command::Transmute {
+                kind: "lowercase|slugify".to_string(),
+                text: "Hello   \"big  world\" ".to_string()
+            })
+        );
+        assert_eq!(
+            parse("reverse-talk <<END").unwrap(),
+            Some(Command::Heredoc {
+                kind: "reverse-talk".to_string(),
+                delimiter: "END".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parsing_transmutation_arguments() {
+        let transmutations = [usage("truncate", 1), usage("replace", 2)];
+        let parse = |line: &str| Command::parse(line, &transmutations).unwrap();
+        assert_eq!(
+            parse("lowercase|truncate 5 Hello world"),
+            Some(Command::Transmute {
+                kind: "lowercase|truncate 5".to_string(),
+                text: "Hello world".to_string()
+            })
+        );
+        assert_eq!(
+            parse("replace ' ' '|' a b"),
+            Some(Command::Transmute {
+                kind: "replace ' ' '|'".to_string(),
+                text: "a b".to_string()
+            })
+        );
+        assert_eq!(
+            parse("\"truncate 5|uppercase\" hello"),
+            Some(Command::Transmute {
+                kind: "truncate 5|uppercase".to_string(),
+                text: "hello".to_string()
+            })
+        );
+        assert_eq!(
+            parse("csv --query 'where Age > 30'  --format tsv data.csv"),
+            Some(Command::Transmute {
+                kind: "csv".to_string(),
+                text: "--query 'where Age > 30'  --format tsv data.csv".to_string()
+            })
+        );
+        assert_eq!(
+            parse("truncate 3 <<END"),
+            Some(Command::Heredoc {
+                kind: "truncate 3".to_string(),
+                delimiter: "END".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn completing_transmutation_names() {
+        let helper = ReplHelper::new(&[usage("lowercase", 0), usage("slugify", 0)]);
+        let history = DefaultHistory::new();
+        let context = Context::new(&history);
+        let complete = |line: &str| {
+            let (start, pairs) = helper.complete(line, line.len(), &context).unwrap();
+            let names = pairs.into_iter().map(|p| p.replacement).collect::<Vec<_>>();
+            (start, names)
+        };
+
+        assert_eq!(complete("lo"), (0, vec!["lowercase".to_string()]));
+        assert_eq!(complete("lowercase|s"), (10, vec!["slugify".to_string()]));
+        assert_eq!(
+            complete("l"),
+            (0, vec!["lowercase".to_string(), "list".to_string()])
+        );
+        assert_eq!(complete("lowercase sl"), (12, Vec::new()));
+    }
+}
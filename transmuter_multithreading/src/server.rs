@@ -0,0 +1,439 @@
+use std::{
+    io::{self, Read},
+    net::{SocketAddr, ToSocketAddrs},
+    sync::Arc,
+    thread::{self, available_parallelism},
+};
+
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response};
+
+use transmuter_common::{
+    error::{Error, ErrorFormat, Result},
+    transmute::encoding::Encoding,
+    Registry,
+};
+
+/// Options of the `serve` mode.
+#[derive(Debug, PartialEq)]
+pub struct ServeOptions {
+    pub address: SocketAddr,
+    pub workers: usize,
+    /// Requests with a larger body are rejected with `413 Payload Too Large`.
+    pub max_body_size: usize,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            address: SocketAddr::from(([127, 0, 0, 1], 8080)),
+            workers: available_parallelism().map_or(1, |n| n.get()),
+            max_body_size: 1024 * 1024,
+        }
+    }
+}
+
+impl ServeOptions {
+    /// Parses the arguments following `serve`, i.e. `--host <host>`, `--port <port>`,
+    /// `--workers <count>` and `--max-body-size <bytes>`.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut options = Self::default();
+        let mut host = options.address.ip().to_string();
+        let mut port = options.address.port();
+        let mut args = args.iter();
+        while let Some(option) = args.next() {
+            let value = args.next().ok_or_else(|| {
+                Error::InvalidArgument(format!("Missing value for the option {option}"))
+            })?;
+            let invalid = || {
+                Error::InvalidArgument(format!(
+                    "The invalid value provided for the option {option}: {value}"
+                ))
+            };
+            match option.as_str() {
+                "--host" => host = value.clone(),
+                "--port" => port = value.parse().map_err(|_| invalid())?,
+                "--workers" => {
+                    options.workers = value.parse().ok().filter(|w| *w > 0).ok_or_else(invalid)?
+                }
+                "--max-body-size" => {
+                    options.max_body_size = value.parse().map_err(|_| invalid())?
+                }
+                // the errors of the server are printed in the format by the caller
+                "--error-format" => {
+                    value.parse::<ErrorFormat>()?;
+                }
+                _ => {
+                    return Err(Error::InvalidArgument(format!(
+                        "The unsupported serve option provided: {option}"
+                    )))
+                }
+            }
+        }
+        options.address = (host.as_str(), port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!("The host cannot be resolved: {host}"))
+            })?;
+        Ok(options)
+    }
+}
+
+/// HTTP server exposing the transmutations of the registry:
+///
+/// - `GET /transmutations` lists the names, usages and descriptions as JSON,
+/// - `POST /transmute/{kind}` responds with the body transmuted by the kind, which can
+///   be a pipeline such as `lowercase|truncate 80`. A `multipart/form-data` body is
+///   transmuted by the content of its `file` field, e.g. `curl -F file=@data.csv`.
+///
+/// Failures respond with the JSON of [`Error::to_json`].
+pub struct Server {
+    http: Arc<tiny_http::Server>,
+    registry: Arc<Registry>,
+    options: ServeOptions,
+}
+
+impl Server {
+    pub fn bind(options: ServeOptions, registry: Registry) -> Result<Self> {
+        let http = tiny_http::Server::http(options.address).map_err(io::Error::other)?;
+        Ok(Self {
+            http: Arc::new(http),
+            registry: Arc::new(registry),
+            options,
+        })
+    }
+
+    /// The bound address, which differs from the configured one for the port 0.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Handles the requests concurrently on the worker threads until the process exits.
+    pub fn run(self) {
+        let workers = (0..self.options.workers)
+            .map(|_| {
+                let http = Arc::clone(&self.http);
+                let registry = Arc::clone(&self.registry);
+                let max_body_size = self.options.max_body_size;
+                thread::spawn(move || {
+                    for request in http.incoming_requests() {
+                        handle(&registry, max_body_size, request);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn handle(registry: &Registry, max_body_size: usize, mut request: Request) {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let response = match (request.method(), path.as_str()) {
+        (Method::Get, "/transmutations") => {
+            let transmutations = registry
+                .transmutations()
+                .map(|t| {
+                    json!({"name": t.name(), "usage": t.usage(), "description": t.description()})
+                })
+                .collect::<Vec<_>>();
+            json_response(200, json!(transmutations).to_string())
+        }
+        (Method::Post, path) if path.starts_with("/transmute/") => {
+            // the escapes of the kind, e.g. of the pipelines `lowercase%7Cslugify`
+            match Encoding::Percent.decode(&path["/transmute/".len()..]) {
+                Ok(kind) => match read_input(&mut request, max_body_size) {
+                    Ok(input) => transmute(registry, &kind, &input),
+                    Err(response) => response,
+                },
+                Err(err) => error_response(&err),
+            }
+        }
+        (_, "/transmutations") => status_response(405, "Method Not Allowed"),
+        (_, path) if path.starts_with("/transmute/") => status_response(405, "Method Not Allowed"),
+        _ => status_response(404, "Not Found"),
+    };
+    // the client may be gone already, there is nobody to report the failure to
+    let _ = request.respond(response);
+}
+
+type HttpResponse = Response<io::Cursor<Vec<u8>>>;
+
+fn transmute(registry: &Registry, kind: &str, input: &str) -> HttpResponse {
+    match registry
+        .pipeline(kind)
+        .and_then(|pipeline| pipeline.transmute(input))
+    {
+        Ok(result) => {
+            Response::from_string(result).with_header(content_type("text/plain; charset=utf-8"))
+        }
+        Err(err) => error_response(&err),
+    }
+}
+
+/// Reads the body, or the `file` field of a multipart body, up to the size limit.
+fn read_input(
+    request: &mut Request,
+    max_body_size: usize,
+) -> std::result::Result<String, HttpResponse> {
+    let too_large = || {
+        let err = Error::InvalidArgument(format!(
+            "The request body exceeds the limit of {max_body_size} bytes"
+        ));
+        json_response(413, err.to_json())
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length > max_body_size)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_body_size as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| error_response(&e.into()))?;
+    if body.len() > max_body_size {
+        return Err(too_large());
+    }
+
+    let boundary = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Content-Type"))
+        .and_then(|h| multipart_boundary(h.value.as_str()));
+    if let Some(boundary) = boundary {
+        body = multipart_file(&body, &boundary).map_err(|e| error_response(&e))?;
+    }
+    String::from_utf8(body).map_err(|e| error_response(&e.into()))
+}
+
+/// Boundary of the `multipart/form-data; boundary=...` content type.
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    let (mime, parameters) = content_type.split_once(';')?;
+    if !mime.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    parameters.split(';').find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Content of the part named `file`, or of the only part when there is just one.
+fn multipart_file(body: &[u8], boundary: &str) -> Result<Vec<u8>> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut rest = find(body, delimiter.as_bytes())
+        .map(|start| &body[start + delimiter.len()..])
+        .ok_or_else(|| Error::InvalidArgument("The multipart body has no parts".to_string()))?;
+    // every part is followed by the delimiter, the last one by the closing `--`
+    while !rest.starts_with(b"--") {
+        let end = find(rest, format!("\r\n{delimiter}").as_bytes()).ok_or_else(|| {
+            Error::InvalidArgument("The multipart body is not closed".to_string())
+        })?;
+        let part = rest[..end].strip_prefix(b"\r\n").unwrap_or(&rest[..end]);
+        let headers_end = find(part, b"\r\n\r\n").ok_or_else(|| {
+            Error::InvalidArgument("The multipart part has no headers".to_string())
+        })?;
+        let headers = String::from_utf8_lossy(&part[..headers_end]).to_lowercase();
+        parts.push((headers, &part[headers_end + 4..]));
+        rest = &rest[end + 2 + delimiter.len()..];
+    }
+
+    let file = parts
+        .iter()
+        .find(|(headers, _)| headers.contains("name=\"file\""));
+    match (file, parts.as_slice()) {
+        (Some((_, content)), _) | (None, [(_, content)]) => Ok(content.to_vec()),
+        _ => Err(Error::InvalidArgument(
+            "The multipart body should contain the file field".to_string(),
+        )),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn error_response(err: &Error) -> HttpResponse {
+    let status = match err {
+        Error::UnknownTransmutation { .. } => 404,
+        Error::Io(_) => 500,
+        Error::Pipeline { source, .. } if matches!(**source, Error::Io(_)) => 500,
+        _ => 422,
+    };
+    json_response(status, err.to_json())
+}
+
+fn status_response(status: u16, message: &str) -> HttpResponse {
+    json_response(status, json!({ "message": message }).to_string())
+}
+
+fn json_response(status: u16, body: String) -> HttpResponse {
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type("application/json"))
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", value).expect("the header is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, net::TcpStream};
+
+    use transmuter_common::transmute::csv::{CsvSource, CsvTransmutation};
+
+    use super::*;
+
+    fn start(max_body_size: usize) -> SocketAddr {
+        let mut registry = Registry::new();
+        registry.register(CsvTransmutation::new(CsvSource::Text));
+        let options = ServeOptions {
+            address: SocketAddr::from(([127, 0, 0, 1], 0)),
+            workers: 4,
+            max_body_size,
+        };
+        let server = Server::bind(options, registry).unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+        address
+    }
+
+    /// Sends the request and returns the status code and the body of the response.
+    fn request(
+        address: SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &str,
+        body: &[u8],
+    ) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{headers}Content-Length: {}\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[test]
+    fn parsing_options() {
+        let args = "--port 9000 --workers 2 --max-body-size 10"
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let options = ServeOptions::from_args(&args).unwrap();
+        assert_eq!(options.address, SocketAddr::from(([127, 0, 0, 1], 9000)));
+        assert_eq!(options.workers, 2);
+        assert_eq!(options.max_body_size, 10);
+
+        assert!(ServeOptions::from_args(&["--port".to_string()]).is_err());
+        assert!(ServeOptions::from_args(&["--workers".to_string(), "0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn transmuting_body() {
+        let address = start(1024);
+        let (status, body) = request(address, "POST", "/transmute/uppercase", "", b"hello");
+        assert_eq!((status, body.as_str()), (200, "HELLO"));
+
+        let (status, body) = request(
+            address,
+            "POST",
+            "/transmute/lowercase%7Cslugify",
+            "",
+            b"Hello World",
+        );
+        assert_eq!((status, body.as_str()), (200, "hello-world"));
+
+        let (status, _) = request(address, "POST", "/transmute/lower%-1case", "", b"");
+        assert_eq!(status, 422);
+    }
+
+    #[test]
+    fn listing_transmutations() {
+        let address = start(1024);
+        let (status, body) = request(address, "GET", "/transmutations", "", b"");
+        assert_eq!(status, 200);
+        let transmutations: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(transmutations[0]["name"], "lowercase");
+        assert!(body.contains("\"csv\""));
+        assert!(body.contains("\"pad-left <width> <fill>\""));
+    }
+
+    #[test]
+    fn uploading_multipart_csv() {
+        let address = start(1024);
+        let body = "--XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"data.csv\"\r\nContent-Type: text/csv\r\n\r\nName,Age\r\nJohn,30\r\n--XYZ--\r\n";
+        let (status, body) = request(
+            address,
+            "POST",
+            "/transmute/csv",
+            "Content-Type: multipart/form-data; boundary=XYZ\r\n",
+            body.as_bytes(),
+        );
+        assert_eq!(status, 200);
+        assert!(body.contains("| John |  30 |"), "{body}");
+    }
+
+    #[test]
+    fn reporting_errors() {
+        let address = start(16);
+        let (status, body) = request(address, "POST", "/transmute/shout", "", b"hi");
+        assert_eq!(status, 404);
+        assert!(body.contains("\"error\":\"unknown_transmutation\""));
+
+        let (status, body) = request(address, "POST", "/transmute/csv", "", b"a,b\n1,2,3");
+        assert_eq!(status, 422);
+        assert!(body.contains("\"error\":\"csv_parse\""));
+
+        let (status, _) = request(address, "POST", "/transmute/uppercase", "", &[b'a'; 17]);
+        assert_eq!(status, 413);
+
+        let (status, _) = request(address, "GET", "/transmute/uppercase", "", b"");
+        assert_eq!(status, 405);
+    }
+
+    #[test]
+    fn handling_requests_concurrently() {
+        let address = start(1024);
+        let clients = (0..8)
+            .map(|i| {
+                thread::spawn(move || {
+                    let text = format!("text {i}");
+                    let (status, body) =
+                        request(address, "POST", "/transmute/uppercase", "", text.as_bytes());
+                    assert_eq!((status, body), (200, text.to_uppercase()));
+                })
+            })
+            .collect::<Vec<_>>();
+        for client in clients {
+            client.join().unwrap();
+        }
+    }
+}
@@ -1,26 +1,114 @@
-use std::{error::Error, io, process, thread};
+use std::{
+    io::{self, BufWriter, Read, Write},
+    process,
+    sync::{
+        atomic::{AtomicI32, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::available_parallelism,
+};
 
 use colored::Colorize;
+use rustyline::{error::ReadlineError, history::DefaultHistory, Editor};
+use serde_json::Value;
 
-use crate::error::Result;
+use transmuter_common::{
+    error::{Error, ErrorFormat, Result},
+    output::GreenWriter,
+    transmute::arguments::quote,
+};
 
+use crate::{
+    batch::{collect_files, transmute_file, BatchFile, BatchOptions},
+    repl::{self, Command, ReplHelper, TransmutationUsage, CONTINUATION_PROMPT, HELP, PROMPT},
+    worker_pool::{JobResult, OutputOrder, WorkerPool},
+};
+
+/// Runs the processor for every `<command> <input>` received on stdin. The
+/// processor reads the input and writes its result into the provided output.
+///
+/// In the interactive mode the commands run concurrently on a pool of workers,
+/// configured by the leading `--workers <count>` and `--order submission|completion`
+/// arguments. The `batch <command> <inputs>...` mode runs the command over
+/// files on the same pool. The commands are read by a line editor keeping the history and
+/// completing the names of the transmutations set by `with_transmutations`.
+///
+/// The leading `--error-format json` option prints the errors as JSON objects
+/// and a failure exits with the [`Error::exit_code`] of its kind.
 pub struct StdioProcessor<F>
 where
-    F: Fn(&str, &str) -> Result<String> + Send + Sync,
+    F: Fn(&str, &mut dyn Read, &mut dyn Write) -> Result<()> + Send + Sync,
 {
     env_args: Vec<String>,
     processor: Option<F>,
+    workers: usize,
+    order: OutputOrder,
+    error_format: ErrorFormat,
+    transmutations: Vec<TransmutationUsage>,
+}
+
+fn print_error(err: &str) {
+    eprintln!("{}{}", "Error: ".red(), err.red());
 }
 
 impl<F> StdioProcessor<F>
 where
-    F: Fn(&str, &str) -> Result<String> + Send + Sync + 'static,
+    F: Fn(&str, &mut dyn Read, &mut dyn Write) -> Result<()> + Send + Sync + 'static,
 {
     pub fn new(env_args: Vec<String>, processor: F) -> StdioProcessor<F> {
         StdioProcessor {
             env_args,
             processor: Some(processor),
+            workers: available_parallelism().map_or(1, |n| n.get()),
+            order: OutputOrder::Submission,
+            error_format: ErrorFormat::Text,
+            transmutations: Vec::new(),
+        }
+    }
+
+    /// Transmutations listed and completed in the interactive mode, their arguments
+    /// follow the names in the commands.
+    pub fn with_transmutations(mut self, transmutations: Vec<TransmutationUsage>) -> Self {
+        self.transmutations = transmutations;
+        self
+    }
+
+    /// Consumes the leading worker pool options from the arguments.
+    fn parse_pool_options(&mut self) -> Result<()> {
+        while let Some(option) = self.env_args.get(1).filter(|arg| arg.starts_with("--")) {
+            let option = option.clone();
+            let value = self.env_args.get(2).cloned().unwrap_or_default();
+            match option.as_str() {
+                "--workers" => {
+                    self.workers = value.parse::<usize>().ok().filter(|w| *w > 0).ok_or(
+                        Error::InvalidArgument(format!(
+                            "The option --workers requires a positive number, provided: {value}"
+                        )),
+                    )?;
+                }
+                "--error-format" => {
+                    self.error_format = value.parse()?;
+                }
+                "--order" => {
+                    self.order = match value.as_str() {
+                        "submission" => OutputOrder::Submission,
+                        "completion" => OutputOrder::Completion,
+                        _ => {
+                            return Err(Error::InvalidArgument(format!(
+                                "The unsupported order provided: {value}.\nFollowing are supported: [submission, completion]"
+                            )))
+                        }
+                    };
+                }
+                _ => {
+                    return Err(Error::InvalidArgument(format!(
+                        "The unsupported option provided: {option}"
+                    )))
+                }
+            }
+            self.env_args.drain(1..3);
         }
+        Ok(())
     }
 
     fn read_user_input() -> Result<String> {
@@ -29,17 +117,18 @@ where
         Ok(input)
     }
 
-    fn print_error(err: &str) {
-        eprintln!("{}{}", "Error: ".red(), err.red());
-    }
-
     fn print_result(result: &str) {
         println!("{}", result.green());
     }
 
     pub fn run(&mut self) {
+        if let Err(e) = self.parse_pool_options() {
+            self.error_format.exit(&e);
+        }
         if self.env_args.len() < 2 {
             self.interactive_mode();
+        } else if self.env_args[1] == "batch" {
+            self.batch_mode();
         } else {
             self.one_shot_mode();
         }
@@ -47,78 +136,230 @@ where
     }
 
     fn interactive_mode(&mut self) {
-        let (tx, rx) = flume::unbounded();
-
-        let input_thread = thread::spawn(move || {
-            println!("Provide the transmutation in following format: <command> <input>. Type exit for quitting the program");
-            loop {
-                match Self::read_user_input() {
-                    Ok(line) => {
-                        let input: Vec<&str> = line.split_whitespace().collect();
-                        let Some((kind, text)) = input.split_first() else {
-                            Self::print_error(
-                                "The provided input should have following format: <command> <input>",
-                            );
-                            continue;
-                        };
+        let order = self.order;
+        let error_format = self.error_format;
+        let processor = self.processor.take().unwrap();
+        let mut pool = WorkerPool::new(
+            self.workers,
+            order,
+            move |(kind, text): &(String, String), output: &mut dyn Write| {
+                processor(kind, &mut text.as_bytes(), output)
+            },
+            move |job_result: JobResult<_>| {
+                let job = match order {
+                    OutputOrder::Submission => None,
+                    OutputOrder::Completion => Some(("job", Value::from(job_result.id))),
+                };
+                match job_result.result {
+                    Ok(result) => match job {
+                        Some((_, id)) => Self::print_result(&format!("[job {id}] {result}")),
+                        None => Self::print_result(&result),
+                    },
+                    Err(e) => error_format.print(&e, job),
+                }
+            },
+        );
 
-                        if *kind == "exit" {
-                            // Allow users to exit the interactive mode gracefully
-                            println!("Exiting the interactive mode.");
-                            break;
-                        }
+        let mut editor = match Editor::<ReplHelper, DefaultHistory>::new() {
+            Ok(editor) => editor,
+            Err(e) => error_format.exit(&readline_error(e)),
+        };
+        editor.set_helper(Some(ReplHelper::new(&self.transmutations)));
+        let history = repl::history_path();
+        if let Some(path) = &history {
+            // there is no history file before the first exit
+            let _ = editor.load_history(path);
+        }
 
-                        if let Err(e) = tx.send((kind.to_string(), text.join(" "))) {
-                            Self::print_error(&e.to_string());
-                        }
-                    }
-                    Err(e) => {
-                        Self::print_error(&e.to_string());
-                        break;
-                    }
+        println!("Provide the transmutation in following format: <command> <input>. Chain commands with | (e.g. lowercase|slugify). Type help for more or exit for quitting the program");
+        let cancel = loop {
+            let line = match editor.readline(PROMPT) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break false,
+                Err(e) => {
+                    error_format.print(&readline_error(e), None);
+                    break false;
                 }
-            }
-        });
+            };
+            let _ = editor.add_history_entry(line.as_str());
 
-        let processing_thread = {
-            let processor = self.processor.take().unwrap();
-            thread::spawn(move || {
-                for (kind, text) in rx.iter() {
-                    match processor(&kind, &text) {
-                        Ok(output) => {
-                            Self::print_result(&output);
-                        }
+            let command = match Command::parse(&line, &self.transmutations) {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(e) => {
+                    error_format.print(&e, None);
+                    continue;
+                }
+            };
+            let (kind, text) = match command {
+                Command::Help => {
+                    println!("{HELP}");
+                    continue;
+                }
+                Command::List => {
+                    let usages = self
+                        .transmutations
+                        .iter()
+                        .map(|t| t.usage.as_str())
+                        .collect::<Vec<&str>>();
+                    println!("{}", usages.join("\n"));
+                    continue;
+                }
+                // Allow users to exit the interactive mode gracefully
+                Command::Exit { cancel } => break cancel,
+                Command::Transmute { kind, text } => (kind, text),
+                Command::Heredoc { kind, delimiter } => {
+                    match Self::read_heredoc(&mut editor, &delimiter) {
+                        Ok(text) => (kind, text),
                         Err(e) => {
-                            Self::print_error(&e.to_string());
+                            error_format.print(&e, None);
                             continue;
                         }
                     }
                 }
-            })
+            };
+
+            let id = pool.submit((kind, text));
+            if order == OutputOrder::Completion {
+                println!("Submitted job {id}");
+            }
         };
 
-        let input_thread_result = input_thread.join();
-        let processing_thread_result = processing_thread.join();
+        if let Some(path) = &history {
+            if let Err(e) = editor.save_history(path) {
+                print_error(&format!("Failed to save the history: {e}"));
+            }
+        }
 
-        if let Err(_) = input_thread_result {
-            Self::print_error("Input reading thread failed");
+        let in_flight = pool.in_flight();
+        if cancel {
+            println!("Exiting the interactive mode, cancelling {in_flight} jobs in flight.");
+            pool.cancel();
+            return;
+        }
+        println!("Exiting the interactive mode after {in_flight} jobs in flight finish.");
+        if pool.finish().is_err() {
+            print_error("Input processing thread failed");
             process::exit(1);
         }
-        if let Err(_) = processing_thread_result {
-            Self::print_error("Input processing thread failed");
+    }
+
+    /// Reads the lines up to the one consisting of the delimiter.
+    fn read_heredoc(
+        editor: &mut Editor<ReplHelper, DefaultHistory>,
+        delimiter: &str,
+    ) -> Result<String> {
+        let mut lines = Vec::new();
+        loop {
+            match editor.readline(CONTINUATION_PROMPT) {
+                Ok(line) if line.trim_end() == delimiter => return Ok(lines.join("\n")),
+                Ok(line) => lines.push(line),
+                Err(ReadlineError::Eof) => {
+                    return Err(Error::InvalidArgument(format!(
+                        "The input ended before the closing {delimiter}"
+                    )))
+                }
+                Err(e) => return Err(readline_error(e)),
+            }
+        }
+    }
+
+    /// Transmutes the files on the worker pool and reports the result of each.
+    fn batch_mode(&mut self) {
+        let (options, files) = match BatchOptions::from_args(&self.env_args[2..])
+            .and_then(|options| Ok((collect_files(&options.inputs)?, options)))
+        {
+            Ok((files, options)) => (options, files),
+            Err(e) => self.error_format.exit(&e),
+        };
+        let error_format = self.error_format;
+
+        let processor = self.processor.take().unwrap();
+        let failures = Arc::new(AtomicUsize::new(0));
+        // the batch exits with the code of the first failure
+        let exit_code = Arc::new(AtomicI32::new(0));
+
+        let mut pool = WorkerPool::new(
+            self.workers,
+            OutputOrder::Submission,
+            {
+                let kind = options.kind.clone();
+                let output_dir = options.output.clone();
+                move |file: &BatchFile, output: &mut dyn Write| {
+                    let destination = file.destination(&output_dir);
+                    transmute_file(&processor, &kind, &file.source, &destination)?;
+                    write!(output, "{}", destination.display())?;
+                    Ok(())
+                }
+            },
+            {
+                let failures = Arc::clone(&failures);
+                let exit_code = Arc::clone(&exit_code);
+                move |job_result: JobResult<BatchFile>| {
+                    let source = job_result.job.source.display().to_string();
+                    match job_result.result {
+                        Ok(destination) => {
+                            Self::print_result(&format!("{source} -> {destination}"))
+                        }
+                        Err(e) => {
+                            failures.fetch_add(1, Ordering::SeqCst);
+                            let _ = exit_code.compare_exchange(
+                                0,
+                                e.exit_code(),
+                                Ordering::SeqCst,
+                                Ordering::SeqCst,
+                            );
+                            error_format.print(&e, Some(("file", Value::from(source))));
+                        }
+                    }
+                }
+            },
+        );
+        let count = files.len();
+        for file in files {
+            pool.submit(file);
+        }
+        if pool.finish().is_err() {
+            print_error("Input processing thread failed");
             process::exit(1);
         }
+
+        let failed = failures.load(Ordering::SeqCst);
+        let summary = format!("{} succeeded, {failed} failed", count - failed);
+        if failed > 0 {
+            print_error(&summary);
+            process::exit(exit_code.load(Ordering::SeqCst));
+        }
+        Self::print_result(&summary);
     }
 
+    /// The arguments following the command are the arguments of its last
+    /// transmutation, e.g. `truncate 80`.
     fn one_shot_mode(&mut self) {
-        let error_handler = |e: Box<dyn Error>| {
-            Self::print_error(&e.to_string());
-            process::exit(1)
-        };
         println!("Please enter your text");
-        let input = Self::read_user_input().unwrap_or_else(error_handler);
-        let result = (self.processor.take().unwrap())(&self.env_args[1], &input)
-            .unwrap_or_else(error_handler);
-        Self::print_result(&result);
+        let processor = self.processor.take().unwrap();
+        let kind = self.env_args[2..]
+            .iter()
+            .fold(self.env_args[1].clone(), |kind, arg| {
+                format!("{kind} {}", quote(arg))
+            });
+        let result = Self::read_user_input().and_then(|input| {
+            // the result is written as it is produced, so large outputs are not held in memory
+            let mut output = GreenWriter(BufWriter::new(io::stdout().lock()));
+            processor(&kind, &mut input.as_bytes(), &mut output)?;
+            writeln!(output)?;
+            Ok(output.flush()?)
+        });
+        if let Err(e) = result {
+            self.error_format.exit(&e);
+        }
+    }
+}
+
+fn readline_error(err: ReadlineError) -> Error {
+    match err {
+        ReadlineError::Io(err) => Error::Io(err),
+        err => Error::Io(io::Error::other(err)),
     }
 }
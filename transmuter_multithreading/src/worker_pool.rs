@@ -0,0 +1,335 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    io::{self, Write},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use flume::Sender;
+
+use transmuter_common::error::{Error, Result};
+
+/// Order in which the results of the jobs are reported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputOrder {
+    /// The results are reported in the order the jobs were submitted, a slow job
+    /// holds back the results of the jobs submitted after it.
+    Submission,
+    /// The results are reported as soon as the jobs complete.
+    Completion,
+}
+
+/// Result of the job identified by the id returned from [`WorkerPool::submit`].
+pub struct JobResult<J> {
+    pub id: usize,
+    pub job: J,
+    pub result: std::result::Result<String, Error>,
+}
+
+struct Job<J> {
+    id: usize,
+    job: J,
+}
+
+/// Runs the submitted jobs, e.g. the `<command> <input>` of the interactive mode,
+/// concurrently on a fixed number of worker threads. The results are passed to
+/// the reporter running on its own thread in the configured [`OutputOrder`].
+pub struct WorkerPool<J> {
+    jobs: Sender<Job<J>>,
+    workers: Vec<JoinHandle<()>>,
+    reporter: JoinHandle<()>,
+    cancelled: Arc<AtomicBool>,
+    submitted: usize,
+    reported: Arc<AtomicUsize>,
+}
+
+impl<J: Send + 'static> WorkerPool<J> {
+    pub fn new<F, R>(workers: usize, order: OutputOrder, processor: F, mut reporter: R) -> Self
+    where
+        F: Fn(&J, &mut dyn Write) -> Result<()> + Send + Sync + 'static,
+        R: FnMut(JobResult<J>) + Send + 'static,
+    {
+        let (jobs, job_rx) = flume::unbounded::<Job<J>>();
+        let (result_tx, result_rx) = flume::unbounded::<JobResult<J>>();
+        let processor = Arc::new(processor);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let reported = Arc::new(AtomicUsize::new(0));
+
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let job_rx = job_rx.clone();
+                let result_tx = result_tx.clone();
+                let processor = Arc::clone(&processor);
+                let cancelled = Arc::clone(&cancelled);
+                thread::spawn(move || {
+                    for job in job_rx.iter() {
+                        if cancelled.load(Ordering::SeqCst) {
+                            continue;
+                        }
+                        let mut output = Vec::new();
+                        // a panicking job is reported as failed, so the results
+                        // submitted after it are not held back forever
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                            processor(&job.job, &mut output)
+                        }))
+                        .unwrap_or_else(|payload| Err(panic_error(payload)))
+                        .map(|()| String::from_utf8_lossy(&output).into_owned());
+                        let job_result = JobResult {
+                            id: job.id,
+                            job: job.job,
+                            result,
+                        };
+                        if result_tx.send(job_result).is_err() {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let reporter = {
+            let cancelled = Arc::clone(&cancelled);
+            let reported = Arc::clone(&reported);
+            thread::spawn(move || {
+                // results completed ahead of the ones submitted before them
+                let mut pending = BTreeMap::new();
+                let mut next_id = 1;
+                for job_result in result_rx.iter() {
+                    if cancelled.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let ready = match order {
+                        OutputOrder::Completion => vec![job_result],
+                        OutputOrder::Submission => {
+                            pending.insert(job_result.id, job_result);
+                            let mut ready = Vec::new();
+                            while let Some(job_result) = pending.remove(&next_id) {
+                                ready.push(job_result);
+                                next_id += 1;
+                            }
+                            ready
+                        }
+                    };
+                    for job_result in ready {
+                        reporter(job_result);
+                        reported.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+        };
+
+        Self {
+            jobs,
+            workers,
+            reporter,
+            cancelled,
+            submitted: 0,
+            reported,
+        }
+    }
+
+    /// Queues the job and returns its id. The ids start at 1.
+    pub fn submit(&mut self, job: J) -> usize {
+        self.submitted += 1;
+        let job = Job {
+            id: self.submitted,
+            job,
+        };
+        // the workers only stop receiving once the pool is finished or cancelled
+        let _ = self.jobs.send(job);
+        self.submitted
+    }
+
+    /// Number of the submitted jobs whose results were not reported yet.
+    pub fn in_flight(&self) -> usize {
+        self.submitted - self.reported.load(Ordering::SeqCst)
+    }
+
+    /// Waits until all the submitted jobs are done and their results reported.
+    pub fn finish(self) -> thread::Result<()> {
+        drop(self.jobs);
+        for worker in self.workers {
+            worker.join()?;
+        }
+        self.reporter.join()
+    }
+
+    /// Drops the jobs which have not started yet and stops reporting the
+    /// results. The jobs already running are left to finish in the background.
+    pub fn cancel(self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+fn panic_error(payload: Box<dyn Any + Send>) -> Error {
+    let message = match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (None, Some(message)) => message.clone(),
+        (None, None) => "unknown cause".to_string(),
+    };
+    Error::Io(io::Error::other(format!("The job panicked: {message}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use flume::{Receiver, RecvTimeoutError};
+
+    use super::*;
+
+    /// Only bounds the waiting of a failing test, the passing ones never wait
+    /// for it to elapse.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Kind of the job and the number of its gate.
+    type GatedJob = (&'static str, usize);
+
+    struct Gates {
+        started: Receiver<usize>,
+        releases: Vec<Sender<()>>,
+        reported: Receiver<(usize, String)>,
+    }
+
+    impl Gates {
+        fn release(&self, gate: usize) {
+            self.releases[gate].send(()).unwrap();
+        }
+    }
+
+    fn next<T>(receiver: &Receiver<T>) -> T {
+        receiver.recv_timeout(TIMEOUT).unwrap()
+    }
+
+    /// Pool of jobs which finish only once the test releases their gate and
+    /// write its number. The `fail` and `panic` jobs fail right away.
+    fn gated(workers: usize, order: OutputOrder, gates: usize) -> (WorkerPool<GatedJob>, Gates) {
+        let (started_tx, started) = flume::unbounded();
+        let (releases, waits): (Vec<_>, Vec<_>) = (0..gates).map(|_| flume::bounded(1)).unzip();
+        let (reported_tx, reported) = flume::unbounded();
+        let pool = WorkerPool::new(
+            workers,
+            order,
+            move |&(kind, gate): &GatedJob, output: &mut dyn Write| {
+                match kind {
+                    "fail" => return Err(Error::InvalidArgument("failed".to_string())),
+                    "panic" => panic!("crab"),
+                    _ => {}
+                }
+                started_tx.send(gate).unwrap();
+                waits[gate].recv().unwrap();
+                write!(output, "{gate}")?;
+                Ok(())
+            },
+            move |job_result: JobResult<GatedJob>| {
+                let text = job_result.result.unwrap_or_else(|e| e.to_string());
+                reported_tx.send((job_result.id, text)).unwrap();
+            },
+        );
+        let gates = Gates {
+            started,
+            releases,
+            reported,
+        };
+        (pool, gates)
+    }
+
+    #[test]
+    fn reporting_in_submission_order() {
+        let (mut pool, gates) = gated(4, OutputOrder::Submission, 3);
+        for job in [
+            ("wait", 0),
+            ("wait", 1),
+            ("fail", 0),
+            ("panic", 0),
+            ("wait", 2),
+        ] {
+            pool.submit(job);
+        }
+        let mut started = (0..3).map(|_| next(&gates.started)).collect::<Vec<_>>();
+        started.sort();
+        assert_eq!(started, vec![0, 1, 2]);
+
+        // the jobs done ahead of the first one wait for it
+        gates.release(2);
+        gates.release(1);
+        assert_eq!(
+            gates.reported.recv_timeout(Duration::from_millis(50)),
+            Err(RecvTimeoutError::Timeout)
+        );
+        gates.release(0);
+        pool.finish().unwrap();
+        let results = gates.reported.drain().collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                (1, "0".to_string()),
+                (2, "1".to_string()),
+                (3, "failed".to_string()),
+                (4, "The job panicked: crab".to_string()),
+                (5, "2".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn reporting_in_completion_order() {
+        let (mut pool, gates) = gated(3, OutputOrder::Completion, 3);
+        for gate in 0..3 {
+            pool.submit(("wait", gate));
+        }
+        (0..3).for_each(|_| {
+            next(&gates.started);
+        });
+        for gate in [1, 2, 0] {
+            gates.release(gate);
+            assert_eq!(next(&gates.reported), (gate + 1, gate.to_string()));
+        }
+        pool.finish().unwrap();
+    }
+
+    #[test]
+    fn running_jobs_concurrently() {
+        let (mut pool, gates) = gated(4, OutputOrder::Submission, 4);
+        for gate in 0..4 {
+            pool.submit(("wait", gate));
+        }
+        // every job starts before any of them is released
+        (0..4).for_each(|_| {
+            next(&gates.started);
+        });
+        (0..4).for_each(|gate| gates.release(gate));
+        pool.finish().unwrap();
+        assert_eq!(gates.reported.drain().count(), 4);
+    }
+
+    #[test]
+    fn cancelling_queued_jobs() {
+        let (mut pool, gates) = gated(1, OutputOrder::Submission, 2);
+        pool.submit(("wait", 0));
+        pool.submit(("wait", 1));
+        assert_eq!(next(&gates.started), 0);
+        assert_eq!(pool.in_flight(), 2);
+
+        // the running job is left to finish after the pool is cancelled
+        pool.cancel();
+        gates.release(0);
+        gates.release(1);
+        assert_eq!(
+            gates.started.recv_timeout(TIMEOUT),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        assert_eq!(
+            gates.reported.recv_timeout(TIMEOUT),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    }
+}
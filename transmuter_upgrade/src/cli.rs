@@ -0,0 +1,311 @@
+use std::path::PathBuf;
+
+use clap::{
+    builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+    CommandFactory, FromArgMatches, Parser, ValueEnum,
+};
+use clap_complete::Shell;
+
+use transmuter_common::{
+    error::{Error, ErrorFormat, Result},
+    Registry,
+};
+
+/// When the output is colored.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ColorMode {
+    /// Only when writing to a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+/// Transmutes the text read from stdin or a file.
+#[derive(Debug, Parser)]
+#[command(version, disable_help_subcommand = true)]
+pub struct Cli {
+    /// Transmutation to apply. The arguments and the options of the transmutation
+    /// follow it, e.g. `truncate 80`, `csv --format json`, `stats --top 5` or
+    /// `hmac-sha256 secret`.
+    #[arg(index = 1)]
+    pub kind: Option<String>,
+
+    /// Arguments of the transmutation, or the options of the csv, stats and HMAC ones.
+    #[arg(
+        index = 2,
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        hide = true
+    )]
+    pub args: Vec<String>,
+
+    /// Reads the input from the file instead of stdin.
+    #[arg(short, long, value_name = "FILE")]
+    pub input: Option<PathBuf>,
+
+    /// Writes the result into the file instead of stdout.
+    #[arg(short, long, value_name = "FILE", conflicts_with = "in_place")]
+    pub output: Option<PathBuf>,
+
+    /// Replaces the content of the input file with the result.
+    #[arg(long, requires = "input")]
+    pub in_place: bool,
+
+    /// Undoes the transmutation, e.g. `reverse-talk --invert`. Fails for the
+    /// transmutations which are not reversible.
+    #[arg(long)]
+    pub invert: bool,
+
+    /// Does not print the prompt.
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    #[arg(long, value_enum, default_value_t = ColorMode::Auto)]
+    pub color: ColorMode,
+
+    #[arg(
+        long,
+        default_value = "text",
+        value_parser = PossibleValuesParser::new([
+            PossibleValue::new("text"),
+            PossibleValue::new("json")
+                .help("Single line JSON objects with the kind of the error and its exit code"),
+        ])
+            .try_map(|format| format.parse::<ErrorFormat>())
+    )]
+    pub error_format: ErrorFormat,
+
+    /// Prints the completion script for the shell and exits.
+    #[arg(long, value_name = "SHELL")]
+    pub completions: Option<Shell>,
+}
+
+impl Cli {
+    /// Parses the arguments, the help lists the transmutations of the registry.
+    pub fn parse_args(registry: &Registry, args: &[String]) -> Result<Self> {
+        let command = Self::command_for(registry);
+        let mut cli = Self::matches(&command, args)?;
+        // the options following the arguments of the transmutation end up among
+        // them, e.g. the `--invert` of `caesar 3 --invert`, so they are parsed again
+        // right after the kind
+        let (options, rest) = split_options(&command, &cli.args);
+        if !options.is_empty() {
+            let mut args = args[..args.len() - cli.args.len()].to_vec();
+            args.extend(options);
+            cli = Self::matches(&command, &args)?;
+            cli.args = rest;
+        }
+
+        // unknown kinds are reported once the transmutation is needed, so the
+        // completions can still be printed
+        if let Some(kind) = cli.arguments_kind().filter(|k| registry.get(k).is_some()) {
+            registry.stage(kind, &cli.args)?;
+        }
+        Ok(cli)
+    }
+
+    fn matches(command: &clap::Command, args: &[String]) -> Result<Self> {
+        let matches = match command.clone().try_get_matches_from(args) {
+            Ok(matches) => matches,
+            // the help and the version are printed as usual
+            Err(e) if e.use_stderr() && ErrorFormat::from_args(args) == ErrorFormat::Json => {
+                return Err(clap_error(e))
+            }
+            Err(e) => e.exit(),
+        };
+        Self::from_arg_matches(&matches).map_err(clap_error)
+    }
+
+    /// The kind unless it is a csv, stats or HMAC transmutation taking options
+    /// instead of arguments. The HMAC ones take the key either as the argument or
+    /// as the `--key` option.
+    pub fn arguments_kind(&self) -> Option<&str> {
+        self.kind.as_deref().filter(|kind| match *kind {
+            "csv" | "csv-describe" | "stats" => false,
+            kind if kind.starts_with("hmac-") => {
+                !self.args.first().is_some_and(|arg| arg.starts_with("--"))
+            }
+            _ => true,
+        })
+    }
+
+    /// Writes the completion script of the shell into the output.
+    pub fn print_completions(registry: &Registry, shell: Shell, output: &mut dyn std::io::Write) {
+        let names = registry
+            .all_variants()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        // the names are only offered by the completions, unknown ones are reported
+        // by the registry with the list of supported transmutations
+        let mut command = Self::command_for(registry).mut_arg("kind", |arg| {
+            arg.value_parser(PossibleValuesParser::new(names))
+        });
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, output);
+    }
+
+    fn command_for(registry: &Registry) -> clap::Command {
+        let width = registry
+            .transmutations()
+            .map(|t| t.usage().len())
+            .max()
+            .unwrap_or(0);
+        let transmutations = registry
+            .transmutations()
+            .map(|t| format!("  {:width$}  {}", t.usage(), t.description()))
+            .collect::<Vec<String>>()
+            .join("\n");
+        Self::command().after_help(format!("Transmutations:\n{transmutations}"))
+    }
+}
+
+/// Splits the options of the command, with their values, from the rest of the
+/// arguments.
+fn split_options(command: &clap::Command, args: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut options = Vec::new();
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, _)) => (name, true),
+            None => (arg.as_str(), false),
+        };
+        let option = command.get_arguments().find(|option| {
+            option
+                .get_long()
+                .is_some_and(|long| name.strip_prefix("--") == Some(long))
+                || option
+                    .get_short()
+                    .is_some_and(|short| name == format!("-{short}"))
+        });
+        match option {
+            Some(option) => {
+                options.push(arg.clone());
+                if option.get_action().takes_values() && !value {
+                    options.extend(args.next().cloned());
+                }
+            }
+            None => rest.push(arg.clone()),
+        }
+    }
+    (options, rest)
+}
+
+/// The message of the clap error without the usage.
+fn clap_error(err: clap::Error) -> Error {
+    let message = err.to_string();
+    let message = message.lines().next().unwrap_or_default();
+    Error::InvalidArgument(message.trim_start_matches("error: ").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Cli> {
+        let args = args
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        Cli::parse_args(&Registry::new(), &args)
+    }
+
+    #[test]
+    fn parsing_options() {
+        let cli = parse("transmuter_upgrade uppercase -i in.txt -o out.txt --quiet --color never")
+            .unwrap();
+        assert_eq!(cli.kind.as_deref(), Some("uppercase"));
+        assert_eq!(cli.input, Some(PathBuf::from("in.txt")));
+        assert_eq!(cli.output, Some(PathBuf::from("out.txt")));
+        assert!(cli.quiet);
+        assert_eq!(cli.color, ColorMode::Never);
+        assert!(cli.args.is_empty());
+    }
+
+    #[test]
+    fn parsing_csv_options() {
+        let cli =
+            parse("transmuter_upgrade --in-place --input data.csv csv --format json").unwrap();
+        assert_eq!(cli.kind.as_deref(), Some("csv"));
+        assert_eq!(cli.args, vec!["--format", "json"]);
+        assert!(cli.in_place);
+
+        let cli = parse("transmuter_upgrade hmac-sha256 --key secret").unwrap();
+        assert_eq!(cli.arguments_kind(), None);
+        assert_eq!(cli.args, vec!["--key", "secret"]);
+
+        let cli = parse("transmuter_upgrade hmac-sha256 secret").unwrap();
+        assert_eq!(cli.arguments_kind(), Some("hmac-sha256"));
+        assert_eq!(cli.args, vec!["secret"]);
+
+        let cli = parse("transmuter_upgrade stats --top 3").unwrap();
+        assert_eq!(cli.arguments_kind(), None);
+        assert_eq!(cli.args, vec!["--top", "3"]);
+    }
+
+    #[test]
+    fn parsing_transmutation_arguments() {
+        let cli = parse("transmuter_upgrade -q pad-left 10 0").unwrap();
+        assert_eq!(cli.arguments_kind(), Some("pad-left"));
+        assert_eq!(cli.args, vec!["10", "0"]);
+        assert!(parse("transmuter_upgrade pad-left 10").is_err());
+        assert!(parse("transmuter_upgrade truncate wide").is_err());
+    }
+
+    #[test]
+    fn parsing_invert_flag() {
+        assert!(
+            parse("transmuter_upgrade reverse-talk --invert")
+                .unwrap()
+                .invert
+        );
+        let cli = parse("transmuter_upgrade caesar 3 --invert").unwrap();
+        assert_eq!(cli.args, vec!["3"]);
+        assert!(cli.invert);
+
+        let cli = parse("transmuter_upgrade caesar 3 --invert -q --color=never").unwrap();
+        assert_eq!(cli.args, vec!["3"]);
+        assert!(cli.invert && cli.quiet);
+        assert_eq!(cli.color, ColorMode::Never);
+
+        let cli = parse("transmuter_upgrade pad-left 10 --output out.txt 0").unwrap();
+        assert_eq!(cli.args, vec!["10", "0"]);
+        assert_eq!(cli.output, Some(PathBuf::from("out.txt")));
+        assert!(!parse("transmuter_upgrade caesar 3").unwrap().invert);
+    }
+
+    #[test]
+    fn rejecting_unexpected_arguments() {
+        assert!(parse("transmuter_upgrade uppercase extra").is_err());
+    }
+
+    #[test]
+    fn reporting_invalid_options_as_json() {
+        let Err(err) = parse("transmuter_upgrade uppercase --color rainbow --error-format json")
+        else {
+            panic!("the color should be invalid");
+        };
+        assert_eq!(err.exit_code(), 2);
+        assert!(err.to_string().contains("rainbow"));
+    }
+
+    #[test]
+    fn listing_transmutations_in_help() {
+        let help = Cli::command_for(&Registry::new())
+            .render_long_help()
+            .to_string();
+        assert!(help.contains("snake-case"));
+        assert!(help.contains("Joins the words in snake_case"));
+        assert!(help.contains("pad-left <width> <fill>"));
+    }
+
+    #[test]
+    fn generating_completions() {
+        let mut output = Vec::new();
+        Cli::print_completions(&Registry::new(), Shell::Bash, &mut output);
+        let script = String::from_utf8(output).unwrap();
+        assert!(script.contains("reverse-talk"));
+        assert!(script.contains("--in-place"));
+    }
+}
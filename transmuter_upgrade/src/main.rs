@@ -1,64 +1,144 @@
 use std::{
     env,
-    error::Error,
-    io::{self, Read},
-    process,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, IsTerminal, Read, Write},
+    path::Path,
 };
 
-use colored::Colorize;
-use error::{CustomError, Result};
-use transmute::Transmutation;
+use transmuter_common::{
+    config::Config,
+    error::{Error, ErrorFormat, Result},
+    output::GreenWriter,
+    transmute::{
+        csv::{CsvOptions, CsvSource, CsvTransmutation},
+        hash::{HashOptions, HashTransmutation},
+        pipeline::Stage,
+        stats::{StatsOptions, StatsTransmutation},
+        Source,
+    },
+    Registry, Transmute,
+};
+
+use cli::{Cli, ColorMode};
+
+mod cli;
 
-mod error;
-mod transmute;
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let transmutation = parse_transmutation(&args).unwrap_or_else(|e| handle_error(e));
-    let input = read_user_input().unwrap_or_else(|e| handle_error(e));
-    print_result(&input, transmutation);
-}
+    let mut registry = Registry::new();
+    register_csv(&mut registry, CsvOptions::default());
+    Config::load()
+        .and_then(|config| registry.register_aliases(&config.aliases))
+        .unwrap_or_else(|e| ErrorFormat::from_args(&args).exit(&e));
+    let cli = Cli::parse_args(&registry, &args)
+        .unwrap_or_else(|e| ErrorFormat::from_args(&args).exit(&e));
+    let error_format = cli.error_format;
+    colored::control::set_override(match cli.color {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => io::stdout().is_terminal() && io::stderr().is_terminal(),
+    });
 
-fn parse_transmutation(args: &[String]) -> Result<Transmutation> {
-    if args.len() < 2 {
-        return Err(CustomError::new(&format!(
-            "You have to provide transmutation kind as CLI argument.\nFollowing are supported: {}",
-            stringify_possible_transmutations()
-        )));
+    // the arguments following the csv, stats and HMAC kinds are the options of the transmutation
+    let registered = match cli.kind.as_deref() {
+        _ if cli.arguments_kind().is_some() => Ok(()),
+        Some(kind) if kind.starts_with("hmac-") => {
+            HashOptions::from_args(&cli.args).map(|options| register_hmac(&mut registry, options))
+        }
+        Some("stats") => StatsOptions::from_args(&cli.args).map(|options| {
+            registry.register(StatsTransmutation::new(Source::Text).with_options(options))
+        }),
+        _ => CsvOptions::from_args(&cli.args).map(|options| register_csv(&mut registry, options)),
+    };
+    registered.unwrap_or_else(|e| error_format.exit(&e));
+
+    if let Some(shell) = cli.completions {
+        Cli::print_completions(&registry, shell, &mut io::stdout());
+        return;
     }
-    Transmutation::from_str(&args[1]).ok_or(CustomError::new(&format!(
-        "The unsupported transmutation provided: {}.\nFollowing are supported: {}",
-        String::from(&args[1]),
-        stringify_possible_transmutations()
-    )))
+
+    let transmutation =
+        parse_transmutation(&registry, &cli).unwrap_or_else(|e| error_format.exit(&e));
+    print_result(&*transmutation, &cli).unwrap_or_else(|e| error_format.exit(&e));
+}
+
+/// The HMAC transmutations take the key option following the kind, e.g.
+/// `hmac-sha256 --key secret`.
+fn register_hmac(registry: &mut Registry, options: HashOptions) {
+    HashTransmutation::all_variants(Source::Text)
+        .into_iter()
+        .for_each(|t| registry.register(t.with_options(options.clone())));
 }
 
-fn stringify_possible_transmutations() -> String {
-    let transmutations = Transmutation::all_variants()
-        .iter()
-        .map(|v| v.to_string())
-        .collect::<Vec<String>>()
-        .join(", ");
-    format!("[{transmutations}]")
+/// The csv transmutations take the options following the kind, e.g. `csv --format json`.
+fn register_csv(registry: &mut Registry, options: CsvOptions) {
+    registry.register(CsvTransmutation::new(CsvSource::Text).with_options(options.clone()));
+    registry.register(CsvTransmutation::describe(CsvSource::Text).with_options(options));
 }
 
-fn read_user_input() -> Result<String> {
-    println!("Please enter your text.");
-    let mut input = String::new();
-    io::stdin().read_to_string(&mut input)?;
-    Ok(input)
+fn parse_transmutation<'a>(registry: &'a Registry, cli: &Cli) -> Result<Stage<'a>> {
+    let Some(kind) = &cli.kind else {
+        return Err(Error::InvalidArgument(format!(
+            "You have to provide transmutation kind as CLI argument.\nFollowing are supported: {}",
+            registry.stringify_possible_transmutations()
+        )));
+    };
+    let stage = match cli.arguments_kind() {
+        Some(_) => registry.stage(kind, &cli.args)?,
+        None => registry.stage(kind, &[])?,
+    };
+    match cli.invert {
+        true => stage.invert(),
+        false => Ok(stage),
+    }
 }
 
-fn handle_error(err: Box<dyn Error>) -> ! {
-    eprintln!("{}{}", "Error: ".red(), err.to_string().red());
-    process::exit(1);
+/// The input is read and the result written incrementally, so even large CSV
+/// files pass through without being held in memory. The prompt is only shown
+/// when a user types the text into the terminal.
+fn print_result(transmutation: &dyn Transmute, cli: &Cli) -> Result<()> {
+    let mut input: Box<dyn Read> = match &cli.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let output_path = match &cli.output {
+        Some(path) => Some(path.as_path()),
+        None if cli.in_place => cli.input.as_deref(),
+        None => None,
+    };
+    if let Some(path) = output_path {
+        return write_file(transmutation, &mut input, path);
+    }
+
+    let prompt = !cli.quiet && cli.input.is_none() && io::stdin().is_terminal();
+    let terminal = io::stdout().is_terminal();
+    let mut output = GreenWriter(BufWriter::new(io::stdout().lock()));
+    if prompt {
+        println!("Please enter your text.");
+        writeln!(output)?;
+    }
+    transmutation.transmute_stream(&mut input, &mut output)?;
+    if prompt {
+        writeln!(output, "\n")?;
+    } else if terminal {
+        writeln!(output)?;
+    }
+    Ok(output.flush()?)
 }
 
-fn print_result(input: &str, transmutation: Transmutation) {
-    println!(
-        "\n{}\n",
-        transmutation
-            .transmute(&input)
-            .unwrap_or_else(|e| handle_error(e))
-            .green()
-    );
+/// The result is written into a temporary file first, so the input file being
+/// transmuted in place is only replaced once the transmutation succeeds.
+fn write_file(transmutation: &dyn Transmute, input: &mut dyn Read, path: &Path) -> Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary = path.with_file_name(format!(".{file_name}.transmuting"));
+    let result = (|| {
+        let mut output = BufWriter::new(File::create(&temporary)?);
+        transmutation.transmute_stream(input, &mut output)?;
+        output.flush()?;
+        Ok(fs::rename(&temporary, path)?)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
 }